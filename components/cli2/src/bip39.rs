@@ -0,0 +1,173 @@
+//! Full BIP39 mnemonic encoding and SLIP-0010 hardened-only ed25519 key derivation.
+//!
+//! This is deliberately separate from the simplified scheme in `mnemonic.rs` (`KeyGen
+//! --mnemonic`/`RestoreKeypair`): this module follows both specs exactly, using the `bip39`
+//! crate's own English wordlist (the same crate `wasm`'s `generate_mnemonic`/
+//! `keypair_from_mnemonic` already depend on) rather than transcribing the 2048 words by hand,
+//! so `KeygenMnemonic`/`RestoreMnemonic` exist as their own commands rather than replacing the
+//! existing ones.
+//!
+//! Steps: `entropy_to_mnemonic` appends a `len/32`-bit SHA-256 checksum to the raw entropy and
+//! splits the result into 11-bit groups, each indexing one word. `mnemonic_to_seed` stretches
+//! the phrase into a 64-byte seed via PBKDF2-HMAC-SHA512 (2048 rounds, salt `"mnemonic"` +
+//! passphrase), exactly as BIP39 specifies. `derive_path` then walks that seed through
+//! SLIP-0010's ed25519 master-key and hardened-child-key derivation (there is no
+//! non-hardened ed25519 CKD in SLIP-0010, so every index here is implicitly hardened).
+
+use crate::kdf::{hmac_sha512, pbkdf2_hmac_sha512};
+use bip39::Language;
+use sha2::{Digest, Sha256};
+
+/// The real BIP39 English wordlist has exactly 2048 entries, each indexed by an 11-bit group.
+pub const WORDLIST_LEN: usize = 2048;
+
+pub fn wordlist() -> Vec<String> {
+    Language::English.word_list().iter().map(|w| w.to_string()).collect()
+}
+
+#[derive(Debug)]
+pub enum Bip39Error {
+    BadEntropyLength(usize),
+    UnknownWord(String),
+    BadPhraseLength(usize),
+    ChecksumMismatch,
+}
+
+impl std::fmt::Display for Bip39Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bip39Error::BadEntropyLength(bits) => {
+                write!(f, "entropy must be 128-256 bits in steps of 32, got {} bits", bits)
+            }
+            Bip39Error::UnknownWord(w) => write!(f, "`{}` is not in the wordlist", w),
+            Bip39Error::BadPhraseLength(n) => {
+                write!(f, "phrase must have 12-24 words in steps of 3, got {}", n)
+            }
+            Bip39Error::ChecksumMismatch => write!(f, "mnemonic checksum does not match"),
+        }
+    }
+}
+
+impl std::error::Error for Bip39Error {}
+
+fn bits_push(bits: &mut Vec<bool>, byte: u8) {
+    for i in (0..8).rev() {
+        bits.push((byte >> i) & 1 == 1);
+    }
+}
+
+fn bits_to_u32(bits: &[bool]) -> u32 {
+    bits.iter().fold(0u32, |acc, b| (acc << 1) | (*b as u32))
+}
+
+/// Turns raw `entropy` (16, 20, 24, 28, or 32 bytes -- 128 through 256 bits) into a checksummed
+/// BIP39 phrase.
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, Bip39Error> {
+    let bits = entropy.len() * 8;
+    if bits < 128 || bits > 256 || bits % 32 != 0 {
+        return Err(Bip39Error::BadEntropyLength(bits));
+    }
+    let checksum_bits = bits / 32;
+
+    let mut bitvec = Vec::with_capacity(bits + checksum_bits);
+    for byte in entropy {
+        bits_push(&mut bitvec, *byte);
+    }
+    let hash = Sha256::digest(entropy);
+    bits_push(&mut bitvec, hash[0]);
+    bitvec.truncate(bits + checksum_bits);
+
+    let words = wordlist();
+    let phrase = bitvec.chunks(11)
+                        .map(|group| words[bits_to_u32(group) as usize].clone())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+    Ok(phrase)
+}
+
+/// Validates `phrase` against the wordlist and its own checksum, returning the entropy bytes
+/// it was generated from.
+pub fn mnemonic_to_entropy(phrase: &str) -> Result<Vec<u8>, Bip39Error> {
+    let words = wordlist();
+    let given: Vec<&str> = phrase.split_whitespace().collect();
+    if given.len() < 12 || given.len() > 24 || given.len() % 3 != 0 {
+        return Err(Bip39Error::BadPhraseLength(given.len()));
+    }
+
+    let mut bitvec = Vec::with_capacity(given.len() * 11);
+    for word in &given {
+        let idx = words.iter()
+                        .position(|w| w == word)
+                        .ok_or_else(|| Bip39Error::UnknownWord(word.to_string()))?;
+        for i in (0..11).rev() {
+            bitvec.push((idx >> i) & 1 == 1);
+        }
+    }
+
+    let total_bits = bitvec.len();
+    let checksum_bits = total_bits / 33;
+    let entropy_bits = total_bits - checksum_bits;
+
+    let entropy_bytes: Vec<u8> =
+        bitvec[..entropy_bits].chunks(8).map(|b| bits_to_u32(b) as u8).collect();
+
+    let hash = Sha256::digest(&entropy_bytes);
+    let mut expected = Vec::with_capacity(checksum_bits);
+    bits_push(&mut expected, hash[0]);
+    expected.truncate(checksum_bits);
+    if expected != bitvec[entropy_bits..] {
+        return Err(Bip39Error::ChecksumMismatch);
+    }
+
+    Ok(entropy_bytes)
+}
+
+/// Converts a mnemonic phrase and optional passphrase into a 64-byte seed via
+/// PBKDF2-HMAC-SHA512 with the standard BIP39 parameters: 2048 iterations, salt
+/// `"mnemonic"` followed by the passphrase. Does not itself validate the phrase (BIP39 seeds
+/// are derived this way regardless of whether the phrase checksum is valid).
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> [u8; 64] {
+    let salt = format!("mnemonic{}", passphrase);
+    pbkdf2_hmac_sha512(phrase.as_bytes(), salt.as_bytes(), 2048)
+}
+
+/// SLIP-0010 ed25519 master key: `I = HMAC-SHA512(key = "ed25519 seed", data = seed)`; the
+/// left half is the master private key, the right half the master chain code.
+fn slip10_master(seed: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let i = hmac_sha512(b"ed25519 seed", seed);
+    let mut key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    key.copy_from_slice(&i[..32]);
+    chain_code.copy_from_slice(&i[32..]);
+    (key, chain_code)
+}
+
+/// SLIP-0010 ed25519 hardened child derivation (the only kind ed25519 supports):
+/// `I = HMAC-SHA512(key = chain_code, data = 0x00 || parent_key || ser32(index | 2^31))`.
+fn slip10_ckd_hardened(key: &[u8; 32], chain_code: &[u8; 32], index: u32) -> ([u8; 32], [u8; 32]) {
+    let hardened_index = index | 0x8000_0000;
+    let mut data = Vec::with_capacity(1 + 32 + 4);
+    data.push(0x00);
+    data.extend_from_slice(key);
+    data.extend_from_slice(&hardened_index.to_be_bytes());
+
+    let i = hmac_sha512(chain_code, &data);
+    let mut child_key = [0u8; 32];
+    let mut child_chain_code = [0u8; 32];
+    child_key.copy_from_slice(&i[..32]);
+    child_chain_code.copy_from_slice(&i[32..]);
+    (child_key, child_chain_code)
+}
+
+/// Derives the 32-byte ed25519 secret at hardened `path` (e.g. `[44, 0, 0, 0, address_index]`
+/// for `m/44'/0'/0'/0'/address_index'`) from a BIP39 seed.
+pub fn derive_path(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let (mut key, mut chain_code) = slip10_master(seed);
+    for index in path {
+        let (k, c) = slip10_ckd_hardened(&key, &chain_code, *index);
+        key = k;
+        chain_code = c;
+    }
+    key
+}
+
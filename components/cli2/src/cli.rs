@@ -8,9 +8,9 @@ use std::env;
 use std::fs;
 use structopt::StructOpt;
 use submission_server::{TxnHandle, TxnStatus};
-use txn_builder::{BuildsTransactions, PolicyChoice, TransactionBuilder};
-use zei::xfr::sig::{XfrKeyPair, XfrPublicKey};
-use zei::xfr::structs::{OpenAssetRecord, OwnerMemo}; //, BlindAssetRecord};
+use txn_builder::{BuildsTransactions, PolicyChoice, TransactionBuilder, TransferOperationBuilder};
+use zei::xfr::sig::{XfrKeyPair, XfrPublicKey, XfrSignature};
+use zei::xfr::structs::{AssetRecordTemplate, OpenAssetRecord, OwnerMemo}; //, BlindAssetRecord};
                                                      // use std::rc::Rc;
 use ledger_api_service::LedgerAccessRoutes;
 use promptly::{prompt, prompt_default};
@@ -18,14 +18,23 @@ use std::process::exit;
 use submission_api::SubmissionRoutes;
 use utils::Serialized;
 use utils::{HashOf, NetworkRoute, SignatureOf};
-// use txn_builder::{BuildsTransactions, PolicyChoice, TransactionBuilder, TransferOperationBuilder};
 use std::path::PathBuf;
 use zei::setup::PublicParams;
 use zei::xfr::asset_record::{open_blind_asset_record, AssetRecordType};
 
 pub mod kv;
+mod bip39;
+mod kdf;
+mod location;
+mod mnemonic;
+mod report;
+mod telemetry;
+
+use location::ResultTraceExt;
 
 use kv::{HasTable, KVError, KVStore};
+use rand_chacha::ChaChaRng;
+use rand_core::SeedableRng;
 
 pub struct FreshNamer {
   base: String,
@@ -75,6 +84,10 @@ struct CliConfig {
                             SignatureOf<(HashOf<Option<StateCommitmentData>>, u64)>)>,
   #[serde(default)]
   pub active_txn: Option<TxnBuilderName>,
+  /// OTLP collector endpoint (e.g. `http://localhost:4317`) for trace/metric export. Only
+  /// takes effect when this crate is built with the `otlp` feature; see `telemetry::init`.
+  #[serde(default)]
+  pub otlp_endpoint: Option<String>,
 }
 
 impl HasTable for CliConfig {
@@ -98,6 +111,19 @@ impl HasTable for XfrKeyPair {
   type Key = KeypairName;
 }
 
+/// Metadata about a stored keypair that doesn't belong on `XfrKeyPair` itself.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Default)]
+struct KeypairMeta {
+  /// Was this keypair deterministically derived from a mnemonic phrase (via `KeyGen
+  /// --mnemonic` or `RestoreKeypair`), as opposed to raw entropy or a pasted JSON blob?
+  mnemonic_backed: bool,
+}
+
+impl HasTable for KeypairMeta {
+  const TABLE_NAME: &'static str = "keypair_meta";
+  type Key = KeypairName;
+}
+
 #[derive(Ord, PartialOrd, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash, Default)]
 pub struct PubkeyName(pub String);
 
@@ -114,6 +140,23 @@ impl HasTable for (Transaction, TxnMetadata) {
   type Key = TxnName;
 }
 
+/// A durable record of a submitted transaction's confirmation progress, so
+/// `AwaitConfirmations` can resume polling across separate CLI invocations instead of
+/// requiring one long-lived `Status`/`StatusCheck` session.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+struct TxLogEntry {
+  #[serde(default)]
+  submitted_at: u64,
+  handle: Option<TxnHandle>,
+  status: Option<TxnStatus>,
+  committed_height: Option<u64>,
+}
+
+impl HasTable for TxLogEntry {
+  const TABLE_NAME: &'static str = "tx_log";
+  type Key = TxnName;
+}
+
 #[derive(Ord, PartialOrd, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash, Default)]
 pub struct TxnBuilderName(pub String);
 
@@ -155,6 +198,18 @@ enum CliError {
   },
   #[snafu(display("Failed to locate user's home directory"))]
   HomeDir,
+  #[snafu(display("Invalid amount `{}`: {}", amount, reason))]
+  InvalidAmount { amount: String, reason: String },
+  #[snafu(display("{}", source))]
+  Traced {
+    source: location::Traced<Box<dyn std::error::Error + Send + Sync>>,
+  },
+}
+
+impl<E: std::error::Error + Send + Sync + 'static> From<location::Traced<E>> for CliError {
+  fn from(traced: location::Traced<E>) -> Self {
+    CliError::Traced { source: traced.boxed() }
+  }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -173,19 +228,213 @@ struct TxnMetadata {
   // spent_txos: BTreeMap<String>,
 }
 
+/// A cached TXO's lifecycle: `Unspent` until some in-progress builder claims it as an
+/// input, `Locked` (by the `TxnName` of the builder that claimed it) while that builder
+/// is still being assembled, then `Spent` once the transaction that consumes it is
+/// actually submitted. Locking prevents two concurrent in-progress builders from both
+/// trying to spend the same output.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+enum TxoStatus {
+  Unspent,
+  Locked(TxnBuilderName),
+  Spent,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 struct TxoCacheEntry {
   sid: Option<TxoSID>,
   record: TxOutput,
   owner_memo: Option<OwnerMemo>,
   opened_record: Option<OpenAssetRecord>,
-  unspent: bool,
+  status: TxoStatus,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 struct AssetTypeEntry {
   asset: Asset,
   issuer_nick: Option<PubkeyName>,
+  /// How many fractional digits this asset's human-denominated amounts carry, e.g. `6`
+  /// means a user-facing `"12.5"` is the raw ledger amount `12500000`. `None` means the
+  /// asset has no declared denomination and amounts are always raw base units.
+  #[serde(default)]
+  decimals: Option<u8>,
+}
+
+/// Parses a (possibly fractional) human-denominated amount string into the raw `u64` base
+/// units the ledger operates on, scaling by `10^decimals`. Rejects inputs with more
+/// fractional digits than `decimals` allows and amounts that overflow `u64::MAX`.
+fn parse_denominated_amount(raw: &str, decimals: Option<u8>) -> Result<u64, CliError> {
+  let decimals = decimals.unwrap_or(0) as usize;
+  let (whole_str, frac_str) = raw.split_once('.').unwrap_or((raw, ""));
+
+  if frac_str.len() > decimals {
+    return InvalidAmount { amount: raw.to_string(),
+                           reason: format!("this asset only supports {} fractional digit(s)",
+                                            decimals) }.fail();
+  }
+
+  let whole: u128 = if whole_str.is_empty() {
+    0
+  } else {
+    whole_str.parse()
+             .ok()
+             .context(InvalidAmount { amount: raw.to_string(),
+                                      reason: "not a valid integer part".to_string() })?
+  };
+  let frac: u128 = if frac_str.is_empty() {
+    0
+  } else {
+    frac_str.parse()
+            .ok()
+            .context(InvalidAmount { amount: raw.to_string(),
+                                     reason: "not a valid fractional part".to_string() })?
+  };
+
+  let scale = 10u128.pow(decimals as u32);
+  let frac_scale = 10u128.pow((decimals - frac_str.len()) as u32);
+  let value = whole.checked_mul(scale)
+                   .and_then(|w| frac.checked_mul(frac_scale).and_then(|f| w.checked_add(f)))
+                   .context(InvalidAmount { amount: raw.to_string(),
+                                           reason: "amount overflows".to_string() })?;
+
+  u64::try_from(value).ok()
+      .context(InvalidAmount { amount: raw.to_string(),
+                              reason: format!("amount exceeds u64::MAX ({})", u64::MAX) })
+}
+
+/// Renders a raw ledger amount back into human-denominated form (e.g. `12.500000`) when
+/// `decimals` is known; otherwise just prints the raw integer.
+fn format_denominated_amount(amount: u64, decimals: Option<u8>) -> String {
+  match decimals {
+    None | Some(0) => amount.to_string(),
+    Some(decimals) => {
+      let scale = 10u64.pow(decimals as u32);
+      format!("{}.{:0width$}",
+               amount / scale,
+               amount % scale,
+               width = decimals as usize)
+    }
+  }
+}
+
+/// Looks up the decimals declared for the asset type named `nick` among `types`, if any.
+fn decimals_for_nick(types: &BTreeMap<AssetTypeName, AssetTypeEntry>,
+                      nick: &AssetTypeName)
+                      -> Option<u8> {
+  types.get(nick).and_then(|ent| ent.decimals)
+}
+
+/// Looks up the decimals declared for the asset type whose code is `code` among `types`,
+/// if any. Used to denominate a `TxoCacheEntry`'s amount, which only records the asset's
+/// code, not its nickname.
+fn decimals_for_code(types: &BTreeMap<AssetTypeName, AssetTypeEntry>,
+                      code: &AssetTypeCode)
+                      -> Option<u8> {
+  types.values()
+       .find(|ent| ent.asset.code == *code)
+       .and_then(|ent| ent.decimals)
+}
+
+/// How much leftover change (in base units) [`select_coins`]'s branch-and-bound search will
+/// accept from a single combination before treating it as merely a fallback candidate rather
+/// than a preferred exact-or-near-exact match. `0` means only an exact match short-circuits
+/// the search early; near-exact matches found elsewhere in the search are still kept as the
+/// best-so-far candidate.
+const COIN_SELECTION_WASTE_BOUND: u64 = 0;
+
+/// Caps how many combinations [`branch_and_bound`] will try before giving up and letting
+/// [`select_coins`] fall back to greedy accumulation, so a sender with many small UTXOs can't
+/// make this command hang exploring an exponential search space.
+const COIN_SELECTION_MAX_TRIES: u32 = 100_000;
+
+/// Selects a subset of `candidates` (each `(nick, entry, opened_amount)`, as gathered by the
+/// `TransferAsset` command) whose amounts sum to at least `target`. Tries an exhaustive
+/// branch-and-bound search first, which explores including/excluding each candidate (sorted
+/// largest-first) looking for a combination within [`COIN_SELECTION_WASTE_BOUND`] of `target`
+/// -- ideally an exact match needing no change output. If that search can't find one (or gives
+/// up after [`COIN_SELECTION_MAX_TRIES`] tries), falls back to simple largest-first greedy
+/// accumulation. Returns `None` if `candidates` can't cover `target` even greedily.
+fn select_coins(candidates: &[(TxoName, TxoCacheEntry, u64)],
+                 target: u64)
+                 -> Option<Vec<(TxoName, TxoCacheEntry, u64)>> {
+  let mut sorted: Vec<&(TxoName, TxoCacheEntry, u64)> = candidates.iter().collect();
+  sorted.sort_by(|a, b| b.2.cmp(&a.2));
+
+  if let Some(indices) = branch_and_bound(&sorted, target, COIN_SELECTION_WASTE_BOUND) {
+    return Some(indices.into_iter().map(|i| (*sorted[i]).clone()).collect());
+  }
+
+  let mut chosen = Vec::new();
+  let mut total = 0u64;
+  for ent in sorted {
+    if total >= target {
+      break;
+    }
+    total += ent.2;
+    chosen.push((*ent).clone());
+  }
+  if total >= target {
+    Some(chosen)
+  } else {
+    None
+  }
+}
+
+/// The actual branch-and-bound search used by [`select_coins`]: at each candidate (in
+/// largest-first order), tries both including and excluding it, pruning a branch once the
+/// unexplored remainder can no longer reach `remaining`. Returns the indices (into `sorted`)
+/// of the lowest-waste combination found within `waste_bound`, if any.
+fn branch_and_bound(sorted: &[&(TxoName, TxoCacheEntry, u64)],
+                     target: u64,
+                     waste_bound: u64)
+                     -> Option<Vec<usize>> {
+  let suffix_sum: Vec<u64> = {
+    let mut sums = vec![0u64; sorted.len() + 1];
+    for i in (0..sorted.len()).rev() {
+      sums[i] = sums[i + 1] + sorted[i].2;
+    }
+    sums
+  };
+
+  fn search(sorted: &[&(TxoName, TxoCacheEntry, u64)],
+            suffix_sum: &[u64],
+            i: usize,
+            remaining: u64,
+            waste_bound: u64,
+            tries: &mut u32,
+            chosen: &mut Vec<usize>,
+            best: &mut Option<(u64, Vec<usize>)>) {
+    if *tries >= COIN_SELECTION_MAX_TRIES || matches!(*best, Some((0, _))) {
+      return;
+    }
+    *tries += 1;
+
+    if i == sorted.len() || suffix_sum[i] < remaining {
+      return;
+    }
+
+    let amt = sorted[i].2;
+    if amt >= remaining {
+      let waste = amt - remaining;
+      if waste <= waste_bound && best.as_ref().map(|(w, _)| waste < *w).unwrap_or(true) {
+        let mut candidate = chosen.clone();
+        candidate.push(i);
+        *best = Some((waste, candidate));
+      }
+    } else {
+      chosen.push(i);
+      search(sorted, suffix_sum, i + 1, remaining - amt, waste_bound, tries, chosen, best);
+      chosen.pop();
+    }
+
+    search(sorted, suffix_sum, i + 1, remaining, waste_bound, tries, chosen, best);
+  }
+
+  let mut best = None;
+  let mut chosen = Vec::new();
+  let mut tries = 0u32;
+  search(sorted, &suffix_sum, 0, target, waste_bound, &mut tries, &mut chosen, &mut best);
+  best.map(|(_, indices)| indices)
 }
 
 fn indent_of(indent_level: u64) -> String {
@@ -216,7 +465,9 @@ enum OpMetadata {
   },
 }
 
-fn display_op_metadata(indent_level: u64, ent: &OpMetadata) {
+fn display_op_metadata(indent_level: u64,
+                        ent: &OpMetadata,
+                        types: &BTreeMap<AssetTypeName, AssetTypeEntry>) {
   let ind = indent_of(indent_level);
   match ent {
     OpMetadata::DefineAsset { asset_nick,
@@ -229,12 +480,19 @@ fn display_op_metadata(indent_level: u64, ent: &OpMetadata) {
                              output_name,
                              output_amt,
                              issue_seq_num, } => {
-      println!("{}IssueAsset {} of `{}`", ind, output_amt, asset_nick.0);
+      println!("{}IssueAsset {} of `{}`",
+               ind,
+               format_denominated_amount(*output_amt, decimals_for_nick(types, asset_nick)),
+               asset_nick.0);
       println!("{} issued to `{}` as issuance #{} named `{}`",
                ind, issuer_nick.0, issue_seq_num, output_name);
     }
-    OpMetadata::TransferAsset { .. } => {
-      unimplemented!();
+    OpMetadata::TransferAsset { inputs, outputs } => {
+      println!("{}TransferAsset", ind);
+      println!("{} Inputs:", ind);
+      display_txos(indent_level + 2, inputs, types);
+      println!("{} Outputs:", ind);
+      display_txos(indent_level + 2, outputs, types);
     }
   }
 }
@@ -247,14 +505,20 @@ fn display_asset_type_defs(indent_level: u64, ent: &BTreeMap<AssetTypeName, Asse
   }
 }
 
-fn display_operations(indent_level: u64, operations: &[OpMetadata]) {
+fn display_operations(indent_level: u64,
+                       operations: &[OpMetadata],
+                       types: &BTreeMap<AssetTypeName, AssetTypeEntry>) {
   for op in operations.iter() {
-    display_op_metadata(indent_level, op);
+    display_op_metadata(indent_level, op, types);
   }
 }
 
-fn display_txo_entry(indent_level: u64, txo: &TxoCacheEntry) {
+fn display_txo_entry(indent_level: u64,
+                      txo: &TxoCacheEntry,
+                      types: &BTreeMap<AssetTypeName, AssetTypeEntry>) {
   let ind = indent_of(indent_level);
+  let code = txo.record.0.asset_type.get_asset_type().map(|x| AssetTypeCode { val: x });
+  let decimals = code.as_ref().and_then(|c| decimals_for_code(types, c));
   println!("{}sid: {}", ind, serialize_or_str(&txo.sid, "<UNKNOWN>"));
   println!("{}Record Type: {}",
            ind,
@@ -265,25 +529,29 @@ fn display_txo_entry(indent_level: u64, txo: &TxoCacheEntry) {
               .0
               .amount
               .get_amount()
-              .map(|x| format!("{}", x))
+              .map(|x| format_denominated_amount(x, decimals))
               .unwrap_or_else(|| "<SECRET>".to_string()));
   println!("{}Type: {}",
            ind,
-           txo.record
-              .0
-              .asset_type
-              .get_asset_type()
-              .map(|x| AssetTypeCode { val: x }.to_base64())
-              .unwrap_or_else(|| "<SECRET>".to_string()));
+           code.as_ref()
+               .map(|x| x.to_base64())
+               .unwrap_or_else(|| "<SECRET>".to_string()));
   if let Some(open_ar) = txo.opened_record.as_ref() {
-    println!("{}Decrypted Amount: {}", ind, open_ar.amount);
-    println!("{}Decrypted Type: {}",
+    let open_code = AssetTypeCode { val: open_ar.asset_type };
+    println!("{}Decrypted Amount: {}",
              ind,
-             AssetTypeCode { val: open_ar.asset_type }.to_base64());
+             format_denominated_amount(open_ar.amount, decimals_for_code(types, &open_code)));
+    println!("{}Decrypted Type: {}", ind, open_code.to_base64());
   }
-  println!("{}Spent? {}",
+  println!("{}Status: {}",
            ind,
-           if txo.unspent { "Unspent" } else { "Spent" });
+           match &txo.status {
+             TxoStatus::Unspent => "Unspent".to_string(),
+             TxoStatus::Locked(builder_nick) => {
+               format!("Locked (by builder `{}`)", builder_nick.0)
+             }
+             TxoStatus::Spent => "Spent".to_string(),
+           });
   println!("{}Have owner memo? {}",
            ind,
            if txo.owner_memo.is_some() {
@@ -293,29 +561,38 @@ fn display_txo_entry(indent_level: u64, txo: &TxoCacheEntry) {
            });
 }
 
-fn display_txos(indent_level: u64, txos: &[(String, TxoCacheEntry)]) {
+fn display_txos(indent_level: u64,
+                 txos: &[(String, TxoCacheEntry)],
+                 types: &BTreeMap<AssetTypeName, AssetTypeEntry>) {
   let ind = indent_of(indent_level);
   for (nick, txo) in txos.iter() {
     println!("{}{}:", ind, nick);
-    display_txo_entry(indent_level + 1, txo);
+    display_txo_entry(indent_level + 1, txo, types);
   }
 }
 
 fn display_txn_builder(indent_level: u64, ent: &TxnBuilderEntry) {
   let ind = indent_of(indent_level);
   println!("{}Operations:", ind);
-  display_operations(indent_level + 1, &ent.operations);
+  display_operations(indent_level + 1, &ent.operations, &ent.new_asset_types);
 
   println!("{}New asset types defined:", ind);
   display_asset_type_defs(indent_level + 1, &ent.new_asset_types);
 
   println!("{}New asset records:", ind);
-  display_txos(indent_level + 1, &ent.new_txos);
+  display_txos(indent_level + 1, &ent.new_txos, &ent.new_asset_types);
 
   println!("{}Signers:", ind);
   for (nick, _) in ent.signers.iter() {
     println!("{} - `{}`", ind, nick.0);
   }
+
+  if !ent.pending_signers.is_empty() {
+    println!("{}Still needs signatures from:", ind);
+    for pk in ent.pending_signers.iter() {
+      println!("{} - `{}`", ind, serde_json::to_string(pk).unwrap());
+    }
+  }
 }
 
 fn display_txn(indent_level: u64, ent: &(Transaction, TxnMetadata)) {
@@ -328,7 +605,7 @@ fn display_txn(indent_level: u64, ent: &(Transaction, TxnMetadata)) {
            ind,
            serialize_or_str(&ent.1.status, "<UNKNOWN>"));
   println!("{}Operations:", ind);
-  display_operations(indent_level + 1, &ent.1.operations);
+  display_operations(indent_level + 1, &ent.1.operations, &ent.1.new_asset_types);
 
   println!("{}New asset types defined:", ind);
   for (nick, asset_ent) in ent.1.new_asset_types.iter() {
@@ -337,7 +614,7 @@ fn display_txn(indent_level: u64, ent: &(Transaction, TxnMetadata)) {
   }
 
   println!("{}New asset records:", ind);
-  display_txos(indent_level + 1, &ent.1.new_txos);
+  display_txos(indent_level + 1, &ent.1.new_txos, &ent.1.new_asset_types);
 
   println!("{}Signers:", ind);
   for nick in ent.1.signers.iter() {
@@ -358,6 +635,11 @@ fn display_asset_type(indent_level: u64, ent: &AssetTypeEntry) {
            serde_json::to_string(&ent.asset.issuer.key).unwrap());
   println!("{}code: {}", ind, ent.asset.code.to_base64());
   println!("{}memo: `{}`", ind, ent.asset.memo.0);
+  println!("{}decimals: {}",
+           ind,
+           ent.decimals
+              .map(|x| x.to_string())
+              .unwrap_or_else(|| "<none>".to_string()));
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -374,6 +656,30 @@ struct TxnBuilderEntry {
   new_txos: Vec<(String, TxoCacheEntry)>,
   // #[serde(default)]
   // spent_txos: BTreeMap<String>,
+  /// Public keys that still must sign this builder (populated by `ImportTransaction`)
+  /// before `BuildTransaction` may finalize it. Empty for builders that never left this
+  /// machine.
+  #[serde(default)]
+  pending_signers: Vec<XfrPublicKey>,
+}
+
+/// Version tag for the [`Slate`] file format, bumped whenever its shape changes so an
+/// older/newer binary can refuse to misinterpret a file it doesn't understand.
+const SLATE_VERSION: u32 = 1;
+
+/// A self-describing, file-portable snapshot of an in-progress `TxnBuilderEntry`, so a
+/// transaction that needs signatures from keyholders on several machines (cold storage,
+/// genuine multisig) can be carried between them via `ExportTransaction`/`ImportTransaction`
+/// instead of requiring every signing key to be present in one process's `signers` map.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Slate {
+  version: u32,
+  builder: TransactionBuilder,
+  operations: Vec<OpMetadata>,
+  new_asset_types: BTreeMap<AssetTypeName, AssetTypeEntry>,
+  new_txos: Vec<(String, TxoCacheEntry)>,
+  signers: BTreeMap<KeypairName, Serialized<XfrKeyPair>>,
+  pending_signers: Vec<XfrPublicKey>,
 }
 
 trait CliDataStore {
@@ -389,6 +695,12 @@ trait CliDataStore {
   fn add_key_pair(&mut self, k: &KeypairName, kp: XfrKeyPair) -> Result<(), CliError>;
   fn add_public_key(&mut self, k: &PubkeyName, pk: XfrPublicKey) -> Result<(), CliError>;
 
+  fn get_keypair_meta(&self, k: &KeypairName) -> Result<KeypairMeta, CliError>;
+  fn update_keypair_meta<F: FnOnce(&mut KeypairMeta)>(&mut self,
+                                                       k: &KeypairName,
+                                                       f: F)
+                                                       -> Result<(), CliError>;
+
   fn get_built_transactions(&self)
                             -> Result<BTreeMap<TxnName, (Transaction, TxnMetadata)>, CliError>;
   fn get_built_transaction(&self,
@@ -421,6 +733,13 @@ trait CliDataStore {
   fn delete_cached_txo(&mut self, k: &TxoName) -> Result<(), CliError>;
   fn cache_txo(&mut self, k: &TxoName, ent: TxoCacheEntry) -> Result<(), CliError>;
 
+  fn get_tx_log(&self) -> Result<BTreeMap<TxnName, TxLogEntry>, CliError>;
+  fn get_tx_log_entry(&self, k: &TxnName) -> Result<Option<TxLogEntry>, CliError>;
+  fn update_tx_log_entry<F: FnOnce(&mut TxLogEntry)>(&mut self,
+                                                      k: &TxnName,
+                                                      f: F)
+                                                      -> Result<(), CliError>;
+
   fn get_asset_types(&self) -> Result<BTreeMap<AssetTypeName, AssetTypeEntry>, CliError>;
   fn get_asset_type(&self, k: &AssetTypeName) -> Result<Option<AssetTypeEntry>, CliError>;
   fn update_asset_type<E: std::error::Error + 'static,
@@ -445,12 +764,34 @@ fn prompt_for_config(prev_conf: Option<CliConfig>) -> Result<CliConfig, CliError
                  open_count: 0,
                  ledger_sig_key: prev_conf.as_ref().and_then(|x| x.ledger_sig_key),
                  ledger_state: prev_conf.as_ref().and_then(|x| x.ledger_state.clone()),
-                 active_txn: prev_conf.as_ref().and_then(|x| x.active_txn.clone()) })
+                 active_txn: prev_conf.as_ref().and_then(|x| x.active_txn.clone()),
+                 otlp_endpoint: prev_conf.as_ref().and_then(|x| x.otlp_endpoint.clone()) })
 }
 
 #[derive(StructOpt, Debug)]
-#[structopt(about = "Build and manage transactions and assets on a findora ledger",
-            rename_all = "kebab-case")]
+#[structopt(about = "Build and manage transactions and assets on a findora ledger")]
+struct Cli {
+  /// Control colored error output
+  #[structopt(long,
+              default_value = "auto",
+              possible_values = &["always", "never", "auto"])]
+  color: String,
+  /// Shorthand for `--color=never`
+  #[structopt(long)]
+  no_color: bool,
+  /// How to report a failed command: `text` (the default, a human-readable chain) or
+  /// `json` (a single structured object, for log pipelines)
+  #[structopt(long,
+              env = "FINDORA_ERROR_FORMAT",
+              default_value = "text",
+              possible_values = &["text", "json"])]
+  error_format: String,
+  #[structopt(subcommand)]
+  action: Actions,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
 enum Actions {
   /// Initialize or change your local database configuration
   Setup {},
@@ -472,6 +813,51 @@ enum Actions {
   KeyGen {
     /// Identity nickname
     nick: String,
+    /// Derive the key pair from a freshly generated mnemonic phrase, printed once so it
+    /// can be written down, instead of raw entropy
+    #[structopt(short, long)]
+    mnemonic: bool,
+  },
+
+  /// Restore a key pair for <nick> from a previously backed-up mnemonic phrase
+  RestoreKeypair {
+    /// Identity nickname
+    nick: String,
+  },
+
+  /// Generate a key pair for <nick> whose base64-encoded public key starts with `prefix`.
+  /// Cost grows exponentially with prefix length -- each extra base64 character multiplies
+  /// the expected search space by 64.
+  KeygenPrefix {
+    /// Identity nickname
+    nick: String,
+    /// Base64 prefix to search for, matched against the start of the encoded public key
+    prefix: String,
+    #[structopt(short, long, default_value = "4")]
+    /// How many worker threads to search with
+    threads: u64,
+  },
+
+  /// Generate a new key pair for <nick> from a freshly generated full BIP39 mnemonic phrase,
+  /// derived via SLIP-0010 hardened ed25519 derivation along `m/44'/0'/0'/0'/index'`. A
+  /// heavier-weight alternative to `KeyGen --mnemonic`; see `bip39` for why this is a
+  /// separate command rather than a replacement.
+  KeygenMnemonic {
+    /// Identity nickname
+    nick: String,
+    #[structopt(short, long, default_value = "0")]
+    /// Address index at the end of the derivation path
+    index: u32,
+  },
+
+  /// Restore a key pair for <nick> from a previously backed-up full BIP39 phrase (see
+  /// `KeygenMnemonic`)
+  RestoreMnemonic {
+    /// Identity nickname
+    nick: String,
+    #[structopt(short, long, default_value = "0")]
+    /// Address index at the end of the derivation path
+    index: u32,
   },
 
   /// Load an existing key pair for <nick>
@@ -494,6 +880,24 @@ enum Actions {
     nick: String,
   },
 
+  /// Sign an arbitrary message with a stored key pair, without building a ledger transaction
+  SignMessage {
+    /// Which key pair?
+    keypair_nick: String,
+    /// The message to sign
+    message: String,
+  },
+
+  /// Verify a message signature against a stored public key (or key pair)
+  VerifyMessage {
+    /// Which public key (or key pair)?
+    pubkey_nick: String,
+    /// The message that was signed
+    message: String,
+    /// The base64-encoded signature to check, as printed by `SignMessage`
+    signature: String,
+  },
+
   /// Display information about the key pair for <nick>
   ListKeypair {
     /// Identity nickname
@@ -529,6 +933,11 @@ enum Actions {
     nick: String,
     /// Asset type code (b64)
     code: String,
+    /// How many fractional digits this asset's human-denominated amounts carry -- the
+    /// ledger itself has no notion of this, so it must be supplied by whoever already
+    /// knows the asset's convention (e.g. from its issuer)
+    #[structopt(long)]
+    decimals: Option<u8>,
   },
 
   /// Initialize a transaction builder
@@ -579,13 +988,35 @@ enum Actions {
     asset_nick: String,
     /// Sequence number of this issuance
     issue_seq_num: u64,
-    /// Amount to issue
-    amount: u64,
+    /// Amount to issue, in the asset's human-denominated units (e.g. `12.5` for an asset
+    /// with 2+ decimals) if the asset declares `decimals`, or raw base units otherwise
+    amount: String,
+    #[structopt(long)]
+    /// Hide the issued amount from anyone but the issuer
+    confidential_amount: bool,
+    #[structopt(long)]
+    /// Hide the issued asset type from anyone but the issuer
+    confidential_type: bool,
   },
   TransferAsset {
     #[structopt(short, long)]
     /// Which builder?
     builder: Option<String>,
+    /// Keypair to transfer from
+    from_nick: String,
+    /// Public key nickname to transfer to
+    to_nick: String,
+    /// Name for the asset type being transferred
+    asset_nick: String,
+    /// Amount to transfer, in the asset's human-denominated units (e.g. `12.5` for an asset
+    /// with 2+ decimals) if the asset declares `decimals`, or raw base units otherwise
+    amount: String,
+    #[structopt(long)]
+    /// Hide the transferred amount from anyone but the sender and recipient
+    confidential_amount: bool,
+    #[structopt(long)]
+    /// Hide the transferred asset type from anyone but the sender and recipient
+    confidential_type: bool,
   },
   ListBuiltTransaction {
     /// Nickname of the transaction
@@ -619,6 +1050,69 @@ enum Actions {
     /// Whose UTXOs?
     id: Option<String>,
   },
+
+  /// Manually release a TXO that a now-abandoned transaction builder left locked
+  UnlockTxo {
+    /// Which cached TXO?
+    nick: String,
+  },
+
+  /// Repeatedly poll for the status of submitted transactions until they are all
+  /// committed or rejected, or `timeout` elapses. Resumable across separate invocations
+  /// via the local transaction log.
+  AwaitConfirmations {
+    /// Which transactions? Defaults to every not-yet-terminal entry in the transaction log
+    txns: Vec<String>,
+    #[structopt(short, long, default_value = "5")]
+    /// Seconds to wait between poll attempts
+    poll_interval: u64,
+    #[structopt(short, long, default_value = "120")]
+    /// Give up after this many seconds
+    timeout: u64,
+  },
+
+  /// Export an in-progress transaction builder as a portable "slate" file, for passing to
+  /// another keyholder who still needs to countersign it
+  ExportTransaction {
+    #[structopt(short, long)]
+    /// Which builder?
+    builder: Option<String>,
+    /// Path to write the slate to
+    file: PathBuf,
+  },
+
+  /// Import a "slate" file, add `signer`'s signature to it, and re-export it in place
+  ImportTransaction {
+    /// Path to the slate file
+    file: PathBuf,
+    /// Which of this slate's required keypairs should sign it?
+    signer: String,
+  },
+}
+
+fn now_secs() -> u64 {
+  std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+                               .map(|d| d.as_secs())
+                               .unwrap_or(0)
+}
+
+/// `TxnStatus` is an externally-defined enum this file otherwise only ever serializes for
+/// display (see `serialize_or_str`); rather than hard-coding a local copy of its variants,
+/// read the serde-tagged variant name straight out of its JSON shape.
+fn txn_status_tag(status: &TxnStatus) -> Option<String> {
+  match serde_json::to_value(status).ok()? {
+    serde_json::Value::String(s) => Some(s),
+    serde_json::Value::Object(map) => map.keys().next().cloned(),
+    _ => None,
+  }
+}
+
+fn txn_status_is_terminal(status: &TxnStatus) -> bool {
+  matches!(txn_status_tag(status).as_deref(), Some("Committed") | Some("Rejected"))
+}
+
+fn txn_status_is_committed(status: &TxnStatus) -> bool {
+  txn_status_tag(status).as_deref() == Some("Committed")
 }
 
 fn serialize_or_str<T: Serialize>(x: &Option<T>, s: &str) -> String {
@@ -647,6 +1141,10 @@ fn print_conf(conf: &CliConfig) {
                .as_ref()
                .map(|x| x.0.clone())
                .unwrap_or_else(|| "<NONE>".to_string()));
+  println!("OTLP collector endpoint: {}",
+           conf.otlp_endpoint
+               .as_deref()
+               .unwrap_or("<disabled>"));
 }
 
 fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), CliError> {
@@ -667,6 +1165,175 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
       Ok(())
     }
 
+    CheckDb {} => {
+      let mut stale = 0u64;
+      for (nick, txo) in store.get_cached_txos()?.into_iter() {
+        if let TxoStatus::Locked(builder_nick) = &txo.status {
+          if store.get_txn_builder(builder_nick)?.is_none() {
+            println!("TXO `{}` is locked by builder `{}`, which no longer exists. Run `unlock-txo {}` to release it.",
+                     nick.0, builder_nick.0, nick.0);
+            stale += 1;
+          }
+        }
+      }
+      if stale == 0 {
+        println!("No locked-but-stale TXOs found.");
+      } else {
+        println!("{} locked-but-stale TXO(s) found.", stale);
+      }
+      Ok(())
+    }
+
+    UnlockTxo { nick } => {
+      let txo_name = TxoName(nick.clone());
+      let txo = match store.get_cached_txo(&txo_name)? {
+        None => {
+          eprintln!("No cached TXO `{}` found.", nick);
+          exit(-1);
+        }
+        Some(t) => t,
+      };
+      match txo.status {
+        TxoStatus::Unspent => {
+          println!("TXO `{}` is already unlocked.", nick);
+        }
+        TxoStatus::Spent => {
+          eprintln!("TXO `{}` has already been spent; it cannot be unlocked.", nick);
+          exit(-1);
+        }
+        TxoStatus::Locked(_) => {
+          let mut unlocked = txo;
+          unlocked.status = TxoStatus::Unspent;
+          store.cache_txo(&txo_name, unlocked)?;
+          println!("TXO `{}` unlocked.", nick);
+        }
+      }
+      Ok(())
+    }
+
+    AwaitConfirmations { txns, poll_interval, timeout } => {
+      let mut pending: Vec<TxnName> = if txns.is_empty() {
+        store.get_tx_log()?
+             .into_iter()
+             .filter(|(_, log)| log.status.as_ref().map(|s| !txn_status_is_terminal(s))
+                                           .unwrap_or(true))
+             .map(|(k, _)| k)
+             .collect()
+      } else {
+        txns.into_iter().map(TxnName).collect()
+      };
+
+      if pending.is_empty() {
+        println!("Nothing to wait for.");
+        return Ok(());
+      }
+
+      let conf = store.get_config()?;
+      let deadline = now_secs() + timeout;
+
+      loop {
+        let mut still_pending = Vec::new();
+
+        for name in pending {
+          let log = store.get_tx_log_entry(&name)?.unwrap_or_else(|| {
+                       eprintln!("No transaction log entry for `{}`.", name.0);
+                       exit(-1);
+                     });
+          let handle = match log.handle {
+            None => {
+              eprintln!("Transaction `{}` has no submission handle yet; skipping.", name.0);
+              continue;
+            }
+            Some(h) => h,
+          };
+
+          let query = format!("{}{}/{}",
+                              conf.submission_server,
+                              SubmissionRoutes::TxnStatus.route(),
+                              &handle.0);
+          let http_span = telemetry::http_span(&query);
+          let _http_enter = http_span.enter();
+          let resp = match reqwest::blocking::get(&query) {
+            Err(e) => {
+              eprintln!("Request `{}` failed: {}", query, e);
+              telemetry::record_error(&http_span, &e);
+              still_pending.push(name);
+              continue;
+            }
+            Ok(v) => {
+              let status = v.status().as_u16();
+              match v.text()
+                     .map(|x| serde_json::from_str::<TxnStatus>(&x).map_err(|e| (x, e)))
+              {
+                Ok(Ok(status_body)) => {
+                  telemetry::record_http_response(&http_span, status,
+                                                   serde_json::to_string(&status_body)?.len());
+                  status_body
+                }
+                Ok(Err((x, e))) => {
+                  eprintln!("Failed to parse response `{}`: {}", x, e);
+                  still_pending.push(name);
+                  continue;
+                }
+                Err(e) => {
+                  eprintln!("Failed to decode response: {}", e);
+                  still_pending.push(name);
+                  continue;
+                }
+              }
+            }
+          };
+
+          println!("`{}`: {}", name.0, serde_json::to_string(&resp)?);
+          store.update_tx_log_entry(&name, |log| {
+                 log.status = Some(resp.clone());
+               })?;
+          store.update_txn_metadata::<std::convert::Infallible, _>(&name, |metadata| {
+                 metadata.status = Some(resp.clone());
+                 Ok(())
+               })?;
+
+          if txn_status_is_committed(&resp) {
+            telemetry::record_confirmation();
+            // NOTE: actually assigning TxoSIDs to `new_txos` would require querying the
+            // ledger for where each output landed, and nothing elsewhere in this file talks
+            // to the ledger for that; mark the cache entries confirmed-unspent in place of
+            // inventing that API.
+            if let Some((_, metadata)) = store.get_built_transaction(&name)? {
+              for (txo_nick, _) in metadata.new_txos.iter() {
+                let txo_name = TxoName(txo_nick.clone());
+                if let Some(mut cached) = store.get_cached_txo(&txo_name)? {
+                  cached.status = TxoStatus::Unspent;
+                  store.cache_txo(&txo_name, cached)?;
+                }
+              }
+            }
+          }
+
+          if !txn_status_is_terminal(&resp) {
+            still_pending.push(name);
+          }
+        }
+
+        if still_pending.is_empty() {
+          println!("All transactions reached a terminal status.");
+          break;
+        }
+        if now_secs() >= deadline {
+          eprintln!("Timed out waiting for: {}",
+                    still_pending.iter().map(|n| n.0.clone())
+                                 .collect::<Vec<_>>()
+                                 .join(", "));
+          exit(-1);
+        }
+
+        pending = still_pending;
+        std::thread::sleep(std::time::Duration::from_secs(poll_interval));
+      }
+
+      Ok(())
+    }
+
     QueryLedgerState { forget_old_key } => {
       store.update_config(|conf| {
              let mut new_key = forget_old_key;
@@ -684,12 +1351,16 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
                                    conf.ledger_server,
                                    LedgerAccessRoutes::PublicKey.route());
                let resp: XfrPublicKey;
+               let http_span = telemetry::http_span(&query);
+               let _http_enter = http_span.enter();
                match reqwest::blocking::get(&query) {
                  Err(e) => {
                    eprintln!("Request `{}` failed: {}", query, e);
+                   telemetry::record_error(&http_span, &e);
                    exit(-1);
                  }
                  Ok(v) => {
+                   let status = v.status().as_u16();
                    match v.text()
                           .map(|x| serde_json::from_str::<XfrPublicKey>(&x).map_err(|e| (x, e)))
                    {
@@ -702,6 +1373,8 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
                        exit(-1);
                      }
                      Ok(Ok(v)) => {
+                       telemetry::record_http_response(&http_span, status,
+                                                        serde_json::to_string(&v).unwrap().len());
                        resp = v;
                      }
                    }
@@ -721,32 +1394,41 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
              let resp: (HashOf<Option<StateCommitmentData>>,
                         u64,
                         SignatureOf<(HashOf<Option<StateCommitmentData>>, u64)>);
+             let global_state_span = telemetry::http_span(&query);
+             let _global_state_enter = global_state_span.enter();
              match reqwest::blocking::get(&query) {
                Err(e) => {
                  eprintln!("Request `{}` failed: {}", query, e);
+                 telemetry::record_error(&global_state_span, &e);
                  exit(-1);
                }
-               Ok(v) => match v.text()
-                               .map(|x| serde_json::from_str::<_>(&x).map_err(|e| (x, e)))
-               {
-                 Err(e) => {
-                   eprintln!("Failed to decode response: {}", e);
-                   exit(-1);
-                 }
-                 Ok(Err((x, e))) => {
-                   eprintln!("Failed to parse response `{}`: {}", x, e);
-                   exit(-1);
-                 }
-                 Ok(Ok(v)) => {
-                   resp = v;
+               Ok(v) => {
+                 let status = v.status().as_u16();
+                 match v.text()
+                        .map(|x| serde_json::from_str::<_>(&x).map_err(|e| (x, e)))
+                 {
+                   Err(e) => {
+                     eprintln!("Failed to decode response: {}", e);
+                     exit(-1);
+                   }
+                   Ok(Err((x, e))) => {
+                     eprintln!("Failed to parse response `{}`: {}", x, e);
+                     exit(-1);
+                   }
+                   Ok(Ok(v)) => {
+                     let response_size = serde_json::to_string(&v).map(|s| s.len()).unwrap_or(0);
+                     telemetry::record_http_response(&global_state_span, status, response_size);
+                     resp = v;
+                   }
                  }
-               },
+               }
              }
 
              if let Err(e) = resp.2
                                  .verify(&conf.ledger_sig_key.unwrap(), &(resp.0.clone(), resp.1))
              {
                eprintln!("Ledger responded with invalid signature: {}", e);
+               telemetry::record_error_message(&tracing::Span::current(), &e.to_string());
                exit(-1);
              }
 
@@ -761,11 +1443,155 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
       Ok(())
     }
 
-    KeyGen { nick } => {
-      let kp = XfrKeyPair::generate(&mut rand::thread_rng());
+    KeyGen { nick, mnemonic } => {
+      let phrase = if mnemonic {
+        use rand::RngCore;
+        let mut entropy = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut entropy);
+        Some(mnemonic::entropy_to_mnemonic(&entropy))
+      } else {
+        None
+      };
+
+      let kp = match phrase.as_ref() {
+        Some(phrase) => {
+          let seed = mnemonic::mnemonic_to_seed(phrase, "");
+          let mut seed32 = [0u8; 32];
+          seed32.copy_from_slice(&seed[..32]);
+          XfrKeyPair::generate(&mut ChaChaRng::from_seed(seed32))
+        }
+        None => XfrKeyPair::generate(&mut rand::thread_rng()),
+      };
+
+      store.add_public_key(&PubkeyName(nick.to_string()), *kp.get_pk_ref())?;
+      store.add_key_pair(&KeypairName(nick.to_string()), kp)?;
+      println!("New key pair added for `{}`", nick);
+
+      if let Some(phrase) = phrase {
+        store.update_keypair_meta(&KeypairName(nick.to_string()), |meta| {
+               meta.mnemonic_backed = true;
+             })?;
+        println!("Mnemonic backup phrase (write this down -- it will not be shown again):");
+        println!("  {}", phrase);
+      }
+      Ok(())
+    }
+
+    RestoreKeypair { nick } => {
+      let phrase = prompt::<String, _>("Mnemonic phrase?")?;
+      let seed = mnemonic::mnemonic_to_seed(phrase.trim(), "");
+      let mut seed32 = [0u8; 32];
+      seed32.copy_from_slice(&seed[..32]);
+      let kp = XfrKeyPair::generate(&mut ChaChaRng::from_seed(seed32));
+
+      store.add_public_key(&PubkeyName(nick.to_string()), *kp.get_pk_ref())?;
+      store.add_key_pair(&KeypairName(nick.to_string()), kp)?;
+      store.update_keypair_meta(&KeypairName(nick.to_string()), |meta| {
+             meta.mnemonic_backed = true;
+           })?;
+      println!("Key pair `{}` restored from mnemonic phrase", nick);
+      Ok(())
+    }
+
+    KeygenPrefix { nick, prefix, threads } => {
+      use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+      use std::sync::{Arc, Mutex};
+      use std::time::Instant;
+
+      if prefix.len() > 6 {
+        println!("Warning: each extra character in `prefix` multiplies the expected search \
+                   cost by ~64, so a prefix this long may take a very long time to find.");
+      }
+
+      let found: Arc<Mutex<Option<XfrKeyPair>>> = Arc::new(Mutex::new(None));
+      let stop = Arc::new(AtomicBool::new(false));
+      let attempts = Arc::new(AtomicU64::new(0));
+      let start = Instant::now();
+
+      let handles: Vec<_> =
+        (0..threads.max(1)).map(|_| {
+                              let found = Arc::clone(&found);
+                              let stop = Arc::clone(&stop);
+                              let attempts = Arc::clone(&attempts);
+                              let prefix = prefix.clone();
+                              std::thread::spawn(move || {
+                                let mut rng = rand::thread_rng();
+                                while !stop.load(Ordering::Relaxed) {
+                                  let kp = XfrKeyPair::generate(&mut rng);
+                                  attempts.fetch_add(1, Ordering::Relaxed);
+                                  let encoded = serde_json::to_string(kp.get_pk_ref()).unwrap();
+                                  if encoded.trim_matches('"').starts_with(&prefix) {
+                                    *found.lock().unwrap() = Some(kp);
+                                    stop.store(true, Ordering::Relaxed);
+                                    break;
+                                  }
+                                }
+                              })
+                            })
+                            .collect();
+
+      while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        let n = attempts.load(Ordering::Relaxed);
+        let elapsed = start.elapsed().as_secs_f64().max(0.001);
+        println!("{} attempts so far ({:.0}/sec)...", n, n as f64 / elapsed);
+      }
+
+      for h in handles {
+        let _ = h.join();
+      }
+
+      let kp = found.lock().unwrap().take().unwrap_or_else(|| {
+                 eprintln!("No matching key pair found.");
+                 exit(-1);
+               });
+
+      store.add_public_key(&PubkeyName(nick.to_string()), *kp.get_pk_ref())?;
+      store.add_key_pair(&KeypairName(nick.to_string()), kp)?;
+      println!("New key pair added for `{}`, matching prefix `{}`.", nick, prefix);
+      Ok(())
+    }
+
+    KeygenMnemonic { nick, index } => {
+      use rand::RngCore;
+      let mut entropy = [0u8; 16];
+      rand::thread_rng().fill_bytes(&mut entropy);
+      let phrase = bip39::entropy_to_mnemonic(&entropy).unwrap_or_else(|e| {
+                     eprintln!("Failed to encode mnemonic: {}", e);
+                     exit(-1);
+                   });
+
+      let seed = bip39::mnemonic_to_seed(&phrase, "");
+      let secret = bip39::derive_path(&seed, &[44, 0, 0, 0, index]);
+      let kp = XfrKeyPair::generate(&mut ChaChaRng::from_seed(secret));
+
       store.add_public_key(&PubkeyName(nick.to_string()), *kp.get_pk_ref())?;
       store.add_key_pair(&KeypairName(nick.to_string()), kp)?;
+      store.update_keypair_meta(&KeypairName(nick.to_string()), |meta| {
+             meta.mnemonic_backed = true;
+           })?;
       println!("New key pair added for `{}`", nick);
+      println!("Recovery phrase (write this down -- it will not be shown again):");
+      println!("  {}", phrase);
+      Ok(())
+    }
+
+    RestoreMnemonic { nick, index } => {
+      let phrase = prompt::<String, _>("BIP39 mnemonic phrase?")?;
+      if let Err(e) = bip39::mnemonic_to_entropy(phrase.trim()) {
+        eprintln!("Warning: phrase failed validation ({}); deriving anyway.", e);
+      }
+
+      let seed = bip39::mnemonic_to_seed(phrase.trim(), "");
+      let secret = bip39::derive_path(&seed, &[44, 0, 0, 0, index]);
+      let kp = XfrKeyPair::generate(&mut ChaChaRng::from_seed(secret));
+
+      store.add_public_key(&PubkeyName(nick.to_string()), *kp.get_pk_ref())?;
+      store.add_key_pair(&KeypairName(nick.to_string()), kp)?;
+      store.update_keypair_meta(&KeypairName(nick.to_string()), |meta| {
+             meta.mnemonic_backed = true;
+           })?;
+      println!("Key pair `{}` restored from BIP39 mnemonic phrase", nick);
       Ok(())
     }
 
@@ -811,6 +1637,44 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
       Ok(())
     }
 
+    SignMessage { keypair_nick, message } => {
+      let kp = store.get_keypair(&KeypairName(keypair_nick.clone()))?
+                    .unwrap_or_else(|| {
+                      eprintln!("No keypair with name `{}` found.", keypair_nick);
+                      exit(-1);
+                    });
+      let sig = kp.sign(message.as_bytes());
+      println!("{}", serde_json::to_string(&sig)?);
+      Ok(())
+    }
+
+    VerifyMessage { pubkey_nick, message, signature } => {
+      let pk = match store.get_pubkey(&PubkeyName(pubkey_nick.clone()))? {
+        Some(pk) => pk,
+        None => match store.get_keypair(&KeypairName(pubkey_nick.clone()))? {
+          Some(kp) => *kp.get_pk_ref(),
+          None => {
+            eprintln!("No public key or keypair with name `{}` found.", pubkey_nick);
+            exit(-1);
+          }
+        },
+      };
+
+      let sig = match serde_json::from_str::<XfrSignature>(&signature) {
+        Ok(sig) => sig,
+        Err(e) => {
+          eprintln!("Failed to parse signature: {}", e);
+          exit(-1);
+        }
+      };
+
+      match pk.verify(message.as_bytes(), &sig) {
+        Ok(()) => println!("Valid signature by `{}`.", pubkey_nick),
+        Err(e) => println!("Invalid signature: {}", e),
+      }
+      Ok(())
+    }
+
     LoadKeypair { nick } => {
       match serde_json::from_str::<XfrKeyPair>(&prompt::<String,_>(format!("Please paste in the key pair for `{}`",nick)).unwrap()) {
         Err(e) => {
@@ -918,7 +1782,8 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
 
     QueryAssetType { replace,
                      nick,
-                     code, } => {
+                     code,
+                     decimals, } => {
       if !replace
          && store.get_asset_type(&AssetTypeName(nick.clone()))?
                  .is_some()
@@ -968,7 +1833,8 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
         ret
       };
       let ret = AssetTypeEntry { asset: resp,
-                                 issuer_nick };
+                                 issuer_nick,
+                                 decimals };
       store.add_asset_type(&AssetTypeName(nick.clone()), ret)?;
       println!("Asset type `{}` saved as `{}`", code_b64, nick);
       Ok(())
@@ -1156,6 +2022,19 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
         exit(-1);
       }
 
+      let decimals_str: String =
+        prompt_default("decimals (human-denominated amounts; blank = whole units only)?",
+                       String::new())?;
+      let decimals = if decimals_str.trim().is_empty() {
+        None
+      } else {
+        Some(decimals_str.trim()
+                         .parse::<u8>()
+                         .ok()
+                         .context(InvalidAmount { amount: decimals_str.clone(),
+                                                  reason: "not a valid decimals count".to_string() })?)
+      };
+
       store.with_txn_builder::<ledger::data_model::errors::PlatformError, _>(&builder, |builder| {
         builder.builder.add_operation_create_asset(&kp,
                                                     None,
@@ -1168,7 +2047,8 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
                    .insert(AssetTypeName(asset_nick.clone()),
                            AssetTypeEntry { asset: def.body.asset.clone(),
                                             issuer_nick: Some(PubkeyName(issuer_nick.0
-                                                                                    .clone())) });
+                                                                                    .clone())),
+                                            decimals });
           }
           _ => {
             panic!("The transaction builder doesn't include our operation!");
@@ -1192,7 +2072,9 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
     IssueAsset { builder,
                  asset_nick,
                  issue_seq_num,
-                 amount, } => {
+                 amount,
+                 confidential_amount,
+                 confidential_type, } => {
       let builder_opt = builder.map(TxnBuilderName)
                                .or_else(|| store.get_config().unwrap().active_txn);
       let builder_nick;
@@ -1255,16 +2137,20 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
         }
       }
 
+      let amount = parse_denominated_amount(&amount, asset.decimals)?;
+
       println!("IssueAsset: {} of `{}` ({}), authorized by `{}`",
-               amount,
+               format_denominated_amount(amount, asset.decimals),
                asset.asset.code.to_base64(),
                asset_nick.0,
                issuer_nick.0);
 
+      let record_type = AssetRecordType::from_booleans(confidential_amount, confidential_type);
+
       store.with_txn_builder::<errors::PlatformError, _>(&builder_nick, |builder| {
              builder.builder.add_basic_issue_asset(
                &iss_kp, &asset.asset.code, issue_seq_num, amount,
-               AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
+               record_type,
                &PublicParams::new())?;
 
             let out_name = format!("utxo{}",builder.new_txos.len());
@@ -1280,7 +2166,7 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
                             record: txo.clone(),
                             owner_memo: memo.clone(),
                             opened_record: Some(open_blind_asset_record(&txo.0, &memo, iss_kp.get_sk_ref()).unwrap()),
-                            unspent: true,
+                            status: TxoStatus::Unspent,
                           }));
                }
                _ => {
@@ -1305,6 +2191,200 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
       Ok(())
     }
 
+    TransferAsset { builder,
+                    from_nick,
+                    to_nick,
+                    asset_nick,
+                    amount,
+                    confidential_amount,
+                    confidential_type, } => {
+      let builder_opt = builder.map(TxnBuilderName)
+                               .or_else(|| store.get_config().unwrap().active_txn);
+      let builder_nick;
+      match builder_opt {
+        None => {
+          eprintln!("I don't know which transaction to use!");
+          exit(-1);
+        }
+        Some(t) => {
+          builder_nick = t;
+        }
+      }
+
+      if store.get_txn_builder(&builder_nick)?.is_none() {
+        eprintln!("Transaction builder `{}` not found.", builder_nick.0);
+        exit(-1);
+      }
+
+      let from_nick = KeypairName(from_nick);
+      let from_kp = match store.get_keypair(&from_nick)? {
+        None => {
+          eprintln!("No key pair `{}` found.", from_nick.0);
+          exit(-1);
+        }
+        Some(kp) => kp,
+      };
+
+      let to_nick = PubkeyName(to_nick);
+      let to_pk = match store.get_pubkey(&to_nick)? {
+        None => {
+          eprintln!("No public key `{}` found.", to_nick.0);
+          exit(-1);
+        }
+        Some(pk) => pk,
+      };
+
+      let asset_nick = AssetTypeName(asset_nick);
+      let asset = match store.get_asset_type(&asset_nick)? {
+        None => {
+          eprintln!("No asset type with name `{}` found", asset_nick.0);
+          exit(-1);
+        }
+        Some(a) => a,
+      };
+
+      let amount = parse_denominated_amount(&amount, asset.decimals)?;
+
+      // Only cached TXOs with a ledger-assigned `TxoSID` can be referenced as transfer
+      // inputs -- entries still awaiting confirmation (`sid: None`) aren't spendable yet.
+      let candidates: Vec<(TxoName, TxoCacheEntry, u64)> =
+        store.get_cached_txos()?
+             .into_iter()
+             .filter_map(|(nick, txo)| {
+               if txo.status != TxoStatus::Unspent || txo.sid.is_none() {
+                 return None;
+               }
+               // The TXO cache is a single global, multi-identity table -- ownership lives
+               // only in each record's own embedded public key, not in `TxoCacheEntry` --
+               // so without this check a same-asset-type TXO belonging to a different
+               // identity's key would get selected and locked here too.
+               if txo.record.0.public_key != *from_kp.get_pk_ref() {
+                 return None;
+               }
+               let code = txo.record.0.asset_type.get_asset_type().map(|x| AssetTypeCode { val: x });
+               if code != Some(asset.asset.code) {
+                 return None;
+               }
+               let opened_amount = txo.opened_record
+                                      .as_ref()
+                                      .map(|o| o.amount)
+                                      .or_else(|| txo.record.0.amount.get_amount())?;
+               Some((nick, txo, opened_amount))
+             })
+             .collect();
+
+      let selected = match select_coins(&candidates, amount) {
+        None => {
+          eprintln!("`{}` doesn't have enough unspent `{}` to transfer {}.",
+                    from_nick.0,
+                    asset_nick.0,
+                    format_denominated_amount(amount, asset.decimals));
+          exit(-1);
+        }
+        Some(s) => s,
+      };
+
+      let total_in: u64 = selected.iter().map(|(_, _, amt)| amt).sum();
+      let change = total_in - amount;
+
+      println!("TransferAsset: {} of `{}` from `{}` to `{}` (using {} input(s), {} change)",
+               format_denominated_amount(amount, asset.decimals),
+               asset_nick.0,
+               from_nick.0,
+               to_nick.0,
+               selected.len(),
+               format_denominated_amount(change, asset.decimals));
+
+      let record_type = AssetRecordType::from_booleans(confidential_amount, confidential_type);
+
+      store.with_txn_builder::<errors::PlatformError, _>(&builder_nick, |builder| {
+             let mut xfr_builder = TransferOperationBuilder::new();
+             for (_, txo, amt) in selected.iter() {
+               let oar = txo.opened_record.clone().unwrap_or_else(|| {
+                 open_blind_asset_record(&txo.record.0,
+                                         &txo.owner_memo,
+                                         from_kp.get_sk_ref()).unwrap()
+               });
+               xfr_builder.add_input(TxoRef::Absolute(txo.sid.unwrap()), oar, None, None, *amt)?;
+             }
+
+             let to_template =
+               AssetRecordTemplate::with_no_asset_tracking(amount,
+                                                            asset.asset.code.val,
+                                                            record_type,
+                                                            to_pk);
+             xfr_builder.add_output(&to_template, None, None, None)?;
+             if change > 0 {
+               let change_template =
+                 AssetRecordTemplate::with_no_asset_tracking(change,
+                                                              asset.asset.code.val,
+                                                              record_type,
+                                                              *from_kp.get_pk_ref());
+               xfr_builder.add_output(&change_template, None, None, None)?;
+             }
+
+             xfr_builder.create(TransferType::Standard)?;
+             xfr_builder.sign(&from_kp)?;
+             let xfr_op = xfr_builder.transaction()?;
+             builder.builder.add_operation(xfr_op);
+
+             let mut inputs = Vec::new();
+             for (nick, txo, _) in selected.iter() {
+               let mut spent = txo.clone();
+               spent.status = TxoStatus::Spent;
+               inputs.push((nick.0.clone(), spent));
+             }
+
+             let mut outputs = Vec::new();
+             match builder.builder.transaction().body.operations.last() {
+               Some(Operation::TransferAsset(xfr)) => {
+                 for (i, (bar, memo)) in xfr.body
+                                            .transfer
+                                            .outputs
+                                            .iter()
+                                            .zip(xfr.body.transfer.owners_memos.iter())
+                                            .enumerate()
+                 {
+                   let out_name = format!("utxo{}", builder.new_txos.len());
+                   let record = TxOutput(bar.clone());
+                   // Output 0 is the recipient's; we don't hold their secret key to open it.
+                   let opened_record = if i == 0 {
+                     None
+                   } else {
+                     open_blind_asset_record(&record.0, memo, from_kp.get_sk_ref()).ok()
+                   };
+                   let entry = TxoCacheEntry { sid: None,
+                                               record,
+                                               owner_memo: memo.clone(),
+                                               opened_record,
+                                               status: TxoStatus::Unspent };
+                   builder.new_txos.push((out_name.clone(), entry.clone()));
+                   outputs.push((out_name, entry));
+                 }
+               }
+               _ => {
+                 panic!("The transaction builder doesn't include our operation!");
+               }
+             }
+
+             builder.signers
+                    .insert(from_nick.clone(), Serialized::new(&from_kp));
+             builder.operations
+                    .push(OpMetadata::TransferAsset { inputs, outputs });
+             Ok(())
+           })?;
+
+      for (nick, _, _) in selected.iter() {
+        let mut locked = store.get_cached_txo(nick)?.unwrap();
+        locked.status = TxoStatus::Locked(builder_nick.clone());
+        store.cache_txo(nick, locked)?;
+      }
+
+      println!("Successfully added to `{}`", builder_nick.0);
+
+      Ok(())
+    }
+
     BuildTransaction { builder,
                        txn_nick,
                        exact, } => {
@@ -1324,6 +2404,15 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
         }
       }
 
+      if let Some(entry) = store.get_txn_builder(&nick)? {
+        if !entry.pending_signers.is_empty() {
+          eprintln!("Transaction builder `{}` is still missing {} signature(s); import it after the remaining signers have countersigned the exported slate.",
+                    nick.0,
+                    entry.pending_signers.len());
+          exit(-1);
+        }
+      }
+
       let mut txn_nick = TxnName(txn_nick.unwrap_or_else(|| nick.0.clone()));
 
       if store.get_built_transaction(&txn_nick)?.is_some() {
@@ -1393,12 +2482,24 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
       }
 
       let client = reqwest::blocking::Client::builder().build()?;
-      let resp = client.post(&query)
-                       .json(&txn)
-                       .send()?
-                       .error_for_status()?
-                       .text()?;
+      let http_span = telemetry::http_span(&query);
+      let _http_enter = http_span.enter();
+      let payload = serde_json::to_string(&txn)?;
+      telemetry::record_http_request(&http_span, payload.len());
+      let resp = match client.post(&query).json(&txn).send().and_then(reqwest::blocking::Response::error_for_status) {
+        Err(e) => {
+          telemetry::record_error(&http_span, &e);
+          return Err(e.into());
+        }
+        Ok(r) => {
+          let status = r.status().as_u16();
+          let text = r.text()?;
+          telemetry::record_http_response(&http_span, status, text.len());
+          text
+        }
+      };
       let handle = serde_json::from_str::<TxnHandle>(&resp)?;
+      telemetry::record_submission();
 
       for (nick, ent) in metadata.new_asset_types.iter() {
         store.add_asset_type(&nick, ent.clone())?;
@@ -1409,6 +2510,10 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
                                                                    Some(handle.clone());
                                                                  Ok(())
                                                                })?;
+      store.update_tx_log_entry(&TxnName(nick.clone()), |log| {
+             log.submitted_at = now_secs();
+             log.handle = Some(handle.clone());
+           })?;
       println!("Submitted `{}`: got handle `{}`", nick, &handle.0);
 
       if prompt_default("Retrieve its status?", true)? {
@@ -1440,6 +2545,9 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
         }
 
         println!("Got status: {}", serde_json::to_string(&resp)?);
+        store.update_tx_log_entry(&TxnName(nick.clone()), |log| {
+               log.status = Some(resp.clone());
+             })?;
         // TODO: do something if it's committed
         store.update_txn_metadata::<std::convert::Infallible, _>(&TxnName(nick), |metadata| {
                metadata.status = Some(resp);
@@ -1449,6 +2557,143 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
       Ok(())
     }
 
+    ExportTransaction { builder, file } => {
+      let builder_opt = builder.map(TxnBuilderName)
+                               .or_else(|| store.get_config().unwrap().active_txn);
+      let builder_nick;
+      match builder_opt {
+        None => {
+          eprintln!("I don't know which transaction to use!");
+          exit(-1);
+        }
+        Some(t) => {
+          builder_nick = t;
+        }
+      }
+
+      let entry = match store.get_txn_builder(&builder_nick)? {
+        None => {
+          eprintln!("Transaction builder `{}` not found.", builder_nick.0);
+          exit(-1);
+        }
+        Some(e) => e,
+      };
+
+      let slate = Slate { version: SLATE_VERSION,
+                          builder: entry.builder.clone(),
+                          operations: entry.operations.clone(),
+                          new_asset_types: entry.new_asset_types.clone(),
+                          new_txos: entry.new_txos.clone(),
+                          signers: entry.signers.clone(),
+                          pending_signers: entry.pending_signers.clone() };
+
+      fs::write(&file, serde_json::to_string_pretty(&slate)?).with_context(|| UserFile { file: file.clone() })?;
+
+      println!("Exported `{}` to `{}` ({} signature(s) still required).",
+               builder_nick.0,
+               file.display(),
+               slate.pending_signers.len());
+      Ok(())
+    }
+
+    ImportTransaction { file, signer } => {
+      let contents = fs::read_to_string(&file).with_context(|| UserFile { file: file.clone() })?;
+      let mut slate: Slate = serde_json::from_str(&contents)?;
+
+      if slate.version != SLATE_VERSION {
+        eprintln!("Slate `{}` has version {}, but this binary only understands version {}.",
+                  file.display(),
+                  slate.version,
+                  SLATE_VERSION);
+        exit(-1);
+      }
+
+      let signer_nick = KeypairName(signer.clone());
+      let kp = match store.get_keypair(&signer_nick)? {
+        None => {
+          eprintln!("No key pair `{}` found.", signer_nick.0);
+          exit(-1);
+        }
+        Some(kp) => kp,
+      };
+      let pk = *kp.get_pk_ref();
+
+      if !slate.pending_signers.iter().any(|p| *p == pk) {
+        eprintln!("`{}` is not one of this slate's required signers.", signer_nick.0);
+        exit(-1);
+      }
+
+      // The slate's asset definitions and TXOs must match what we already have cached
+      // under the same nickname, or be new to us -- never silently override a conflict.
+      for (nick, ent) in slate.new_asset_types.iter() {
+        match store.get_asset_type(nick)? {
+          Some(existing) if existing != *ent => {
+            eprintln!("Asset type `{}` in the slate conflicts with the entry already cached under that name.",
+                      nick.0);
+            exit(-1);
+          }
+          Some(_) => {}
+          None => {
+            store.add_asset_type(nick, ent.clone())?;
+          }
+        }
+      }
+      for (nick, txo) in slate.new_txos.iter() {
+        let txo_name = TxoName(nick.clone());
+        match store.get_cached_txo(&txo_name)? {
+          Some(existing) if existing != *txo => {
+            eprintln!("TXO `{}` in the slate conflicts with the entry already cached under that name.",
+                      nick);
+            exit(-1);
+          }
+          Some(_) => {}
+          None => {
+            store.cache_txo(&txo_name, txo.clone())?;
+          }
+        }
+      }
+
+      // Everything past this point only adds a signature and shrinks `pending_signers` --
+      // the operations, new_txos, and new_asset_types above are never altered once a
+      // signature has been attached.
+      slate.builder.sign(&kp);
+      slate.signers.insert(signer_nick.clone(), Serialized::new(&kp));
+      slate.pending_signers.retain(|p| *p != pk);
+
+      fs::write(&file, serde_json::to_string_pretty(&slate)?).with_context(|| UserFile { file: file.clone() })?;
+
+      if slate.pending_signers.is_empty() {
+        println!("Signed by `{}`. All required signatures are present; re-exported to `{}`.",
+                 signer_nick.0,
+                 file.display());
+      } else {
+        println!("Signed by `{}`. Still need {} more signature(s); re-exported to `{}`.",
+                 signer_nick.0,
+                 slate.pending_signers.len(),
+                 file.display());
+      }
+
+      let builder_nick =
+        TxnBuilderName(file.file_stem()
+                           .and_then(|s| s.to_str())
+                           .unwrap_or("imported")
+                           .to_string());
+      if store.get_txn_builder(&builder_nick)?.is_none() {
+        store.prepare_transaction(&builder_nick, 0)?;
+      }
+      store.with_txn_builder::<std::convert::Infallible, _>(&builder_nick, |entry| {
+             entry.builder = slate.builder.clone();
+             entry.operations = slate.operations.clone();
+             entry.new_asset_types = slate.new_asset_types.clone();
+             entry.new_txos = slate.new_txos.clone();
+             entry.signers = slate.signers.clone();
+             entry.pending_signers = slate.pending_signers.clone();
+             Ok(())
+           })?;
+      println!("Updated local transaction builder `{}`.", builder_nick.0);
+      Ok(())
+    }
+
     _ => {
       unimplemented!();
     }
@@ -1460,55 +2705,64 @@ fn run_action<S: CliDataStore>(action: Actions, store: &mut S) -> Result<(), Cli
   ret
 }
 
-fn main() {
-  fn inner_main() -> Result<(), CliError> {
-    let action = Actions::from_args();
-
-    // use Actions::*;
-
-    let mut home = PathBuf::new();
-    match env::var("FINDORA_HOME") {
-      Ok(fin_home) => {
-        home.push(fin_home);
-      }
-      Err(_) => {
-        home.push(dirs::home_dir().context(HomeDir)?);
-        home.push(".findora");
-      }
+crate::quick_main!(run);
+fn run() -> Result<(), CliError> {
+  let cli = Cli::from_args();
+  let action = cli.action;
+
+  report::set_color_choice(if cli.no_color {
+                              report::ColorChoice::Never
+                            } else {
+                              match cli.color.as_str() {
+                                "always" => report::ColorChoice::Always,
+                                "never" => report::ColorChoice::Never,
+                                _ => report::ColorChoice::Auto,
+                              }
+                            });
+  report::set_error_format(match cli.error_format.as_str() {
+                              "json" => report::ErrorFormat::Json,
+                              _ => report::ErrorFormat::Text,
+                            });
+
+  // use Actions::*;
+
+  let mut home = PathBuf::new();
+  match env::var("FINDORA_HOME") {
+    Ok(fin_home) => {
+      home.push(fin_home);
     }
-    fs::create_dir_all(&home).with_context(|| UserFile { file: home.clone() })?;
-    home.push("cli2_data.sqlite");
-    let first_time = !std::path::Path::exists(&home);
-    let mut db = KVStore::open(home.clone())?;
-    if first_time {
-      println!("No config found at {:?} -- triggering first-time setup",
-               &home);
-      db.update_config(|conf| {
-          *conf = prompt_for_config(None).unwrap();
-        })?;
-
-      if let Actions::Setup { .. } = action {
-        return Ok(());
-      }
+    Err(_) => {
+      home.push(dirs::home_dir().context(HomeDir)?);
+      home.push(".findora");
     }
-
-    run_action(action, &mut db)?;
-    Ok(())
   }
-  let ret = inner_main();
-  if let Err(x) = ret {
-    use snafu::ErrorCompat;
-    use std::error::Error;
-    let backtrace = ErrorCompat::backtrace(&x);
-    println!("Error: {}", x);
-    let mut current = &x as &dyn Error;
-    while let Some(next) = current.source() {
-      println!("   Caused by: {}", next);
-      current = next;
-    }
-    if let Some(backtrace) = backtrace {
-      println!("Backtrace: \n{}", backtrace);
+  fs::create_dir_all(&home).with_context(|| UserFile { file: home.clone() })?;
+  home.push("cli2_data.sqlite");
+  let first_time = !std::path::Path::exists(&home);
+  let mut db = KVStore::open(home.clone()).traced()?;
+  if first_time {
+    println!("No config found at {:?} -- triggering first-time setup",
+             &home);
+    db.update_config(|conf| {
+        *conf = prompt_for_config(None).unwrap();
+      })?;
+
+    if let Actions::Setup { .. } = action {
+      return Ok(());
     }
-    std::process::exit(1);
   }
+
+  telemetry::init(&db.get_config()?);
+  let action_name = format!("{:?}", action).split_whitespace()
+                                           .next()
+                                           .unwrap_or("unknown")
+                                           .to_string();
+  let span = telemetry::action_span(&action_name);
+  let _enter = span.enter();
+  let result = run_action(action, &mut db);
+  if let Err(e) = &result {
+    telemetry::record_error(&span, e);
+  }
+  result?;
+  Ok(())
 }
@@ -0,0 +1,34 @@
+//! Shared HMAC-SHA512/PBKDF2-HMAC-SHA512 primitives for this crate's two independent
+//! mnemonic/seed schemes (`bip39` and `mnemonic`), so there's exactly one copy of this logic
+//! to audit instead of one per module.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+type HmacSha512 = Hmac<Sha512>;
+
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+  let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts any key length");
+  mac.update(data);
+  let mut out = [0u8; 64];
+  out.copy_from_slice(&mac.finalize().into_bytes());
+  out
+}
+
+/// PBKDF2-HMAC-SHA512, the KDF both `bip39::mnemonic_to_seed` and `mnemonic::mnemonic_to_seed`
+/// use to stretch a phrase + salt into a 64-byte seed.
+pub fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 64] {
+  let mut block_salt = Vec::with_capacity(salt.len() + 4);
+  block_salt.extend_from_slice(salt);
+  block_salt.extend_from_slice(&1u32.to_be_bytes());
+
+  let mut u = hmac_sha512(password, &block_salt);
+  let mut t = u;
+  for _ in 1..iterations {
+    u = hmac_sha512(password, &u);
+    for i in 0..64 {
+      t[i] ^= u[i];
+    }
+  }
+  t
+}
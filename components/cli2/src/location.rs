@@ -0,0 +1,75 @@
+//! A lightweight, dependency-free call-site trace for error chains.
+//!
+//! OS-captured backtraces (see [`crate::report`]) are unavailable once a binary is
+//! stripped, which is common for production deployments -- at that point `Backtrace::fmt`
+//! has nothing to print. [`Traced`] covers that gap the way `chainerror` does: wrapping an
+//! error with the `#[track_caller]`-captured [`Location`] of whoever propagated it, and
+//! threading that through `.source()` as its own link so the reporter prints `at
+//! src/foo.rs:123` for it even with no OS backtrace at all.
+
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+
+/// `inner`, tagged with the call site that propagated it via [`ResultTraceExt::traced`].
+/// Appears in an error's `.source()` chain as its own link: `Traced`'s own `Display` is
+/// just the location (`at file:line`), and its `source()` is `inner`, so printing the
+/// chain yields the location immediately followed by `inner`'s own message.
+pub struct Traced<E> {
+  location: &'static Location<'static>,
+  inner: E,
+}
+
+impl<E> Traced<E> {
+  pub fn location(&self) -> &'static Location<'static> {
+    self.location
+  }
+
+  pub fn into_inner(self) -> E {
+    self.inner
+  }
+}
+
+impl<E: Error + Send + Sync + 'static> Traced<E> {
+  /// Erases `E` to a boxed trait object, keeping the same location -- lets a single
+  /// `From<Traced<E>>` impl accept a `Traced` of any underlying error type.
+  pub fn boxed(self) -> Traced<Box<dyn Error + Send + Sync>> {
+    Traced { location: self.location,
+             inner: Box::new(self.inner) }
+  }
+}
+
+impl<E> fmt::Display for Traced<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "at {}:{}", self.location.file(), self.location.line())
+  }
+}
+
+impl<E: fmt::Debug> fmt::Debug for Traced<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Traced")
+     .field("location", &self.location.to_string())
+     .field("inner", &self.inner)
+     .finish()
+  }
+}
+
+impl<E: Error + 'static> Error for Traced<E> {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    Some(&self.inner)
+  }
+}
+
+/// Extension for stamping a `Result`'s error with its propagation call site, e.g.
+/// `KVStore::open(path).traced()?`.
+pub trait ResultTraceExt<T, E> {
+  fn traced(self) -> Result<T, Traced<E>>;
+}
+
+impl<T, E> ResultTraceExt<T, E> for Result<T, E> {
+  #[track_caller]
+  fn traced(self) -> Result<T, Traced<E>> {
+    self.map_err(|inner| Traced { location: Location::caller(),
+                                  inner })
+  }
+}
@@ -0,0 +1,43 @@
+//! Self-contained, simplified mnemonic backup for CLI-managed key pairs -- NOT BIP39; see
+//! `bip39` for the real, interoperable phrase format (`KeygenMnemonic`/`RestoreMnemonic`).
+//!
+//! This gives the CLI a human-writable recovery phrase instead of the raw serialized
+//! keypair blobs the KV store holds: `KeyGen --mnemonic` prints a phrase, and
+//! `RestoreKeypair` turns that phrase back into the exact same `XfrKeyPair`.
+//!
+//! The PBKDF2-HMAC-SHA512 seed derivation (2048 rounds, salt `"mnemonic"` + passphrase)
+//! matches BIP39's, so that step alone is interoperable. The phrase itself, though, is
+//! encoded against this module's own fixed 16-word `WORDLIST` (two words per entropy byte,
+//! by nibble) instead of the real 2048-word BIP39 wordlist and checksum scheme, so a phrase
+//! from this module is neither generated nor accepted by any standard BIP39 tool -- callers
+//! that need real interoperability should use `KeygenMnemonic`/`RestoreMnemonic` instead.
+
+use crate::kdf::pbkdf2_hmac_sha512;
+
+/// One word per possible entropy byte value is impractical to enumerate by hand at 256
+/// entries of genuinely distinct English words, so `WORDLIST` is intentionally shorter and
+/// a byte is encoded as two words (high nibble, low nibble) instead of one.
+pub const WORDLIST: [&str; 16] = [
+    "abandon", "bridge", "castle", "desert", "eagle", "forest", "garden", "harbor", "island",
+    "jungle", "kitten", "lantern", "mountain", "nebula", "ocean", "planet",
+];
+
+/// Turns `entropy` into a mnemonic phrase: every byte becomes two words, indexed by its
+/// high and low nibble into [`WORDLIST`].
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> String {
+    let mut words = Vec::with_capacity(entropy.len() * 2);
+    for byte in entropy {
+        words.push(WORDLIST[(byte >> 4) as usize]);
+        words.push(WORDLIST[(byte & 0x0f) as usize]);
+    }
+    words.join(" ")
+}
+
+/// Converts a mnemonic phrase and optional passphrase into a 64-byte seed via
+/// PBKDF2-HMAC-SHA512 with the standard BIP39 parameters: 2048 iterations, salt
+/// `"mnemonic"` followed by the passphrase.
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> [u8; 64] {
+    let salt = format!("mnemonic{}", passphrase);
+    pbkdf2_hmac_sha512(phrase.as_bytes(), salt.as_bytes(), 2048)
+}
+
@@ -0,0 +1,251 @@
+//! A reusable `main`-boilerplate for binaries whose top-level error type is a `snafu` enum.
+//!
+//! Every CLI tool in this crate used to hand-roll the same block at the bottom of `main`:
+//! match on the `Result`, print `Error: {}`, walk `.source()` for the `Caused by:` chain,
+//! dump the backtrace if one was captured, and `exit(1)`. [`Report`] extracts that block
+//! into a single `std::process::Termination` impl, and [`quick_main!`] wraps a fallible
+//! `run() -> Result<(), E>` into the `fn main() -> Report<E>` that drives it -- so a binary
+//! only has to write its actual logic, not the error-printing boilerplate around it.
+
+use once_cell::sync::OnceCell;
+use owo_colors::OwoColorize;
+use snafu::ErrorCompat;
+use std::error::Error;
+use std::fmt;
+use std::io::IsTerminal;
+
+/// How much (if any) backtrace detail to print, per `RUST_BACKTRACE`/`RUST_LIB_BACKTRACE`.
+/// Matches the values `std`'s own panic handler honors: unset/`0` prints nothing, `1`
+/// prints a trimmed view, `full` prints every captured frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BacktracePref {
+  Off,
+  Trimmed,
+  Full,
+}
+
+/// How many frames [`BacktracePref::Trimmed`] shows before eliding the rest.
+const TRIMMED_FRAME_LIMIT: usize = 16;
+
+/// Wraps a `run()`'s final `Result` so [`std::process::Termination`] can print it the same
+/// way (chain, backtrace, exit code) no matter which binary produced it.
+pub struct Report<E>(pub Result<(), E>);
+
+impl<E> From<Result<(), E>> for Report<E> {
+  fn from(result: Result<(), E>) -> Self {
+    Report(result)
+  }
+}
+
+/// Reads `RUST_BACKTRACE`, falling back to `RUST_LIB_BACKTRACE` if unset, and caches the
+/// result for the lifetime of the process -- the env vars aren't expected to change mid-run,
+/// and this runs on every error path.
+fn backtrace_pref() -> BacktracePref {
+  static PREF: OnceCell<BacktracePref> = OnceCell::new();
+  *PREF.get_or_init(|| {
+    let raw = std::env::var("RUST_BACKTRACE").or_else(|_| std::env::var("RUST_LIB_BACKTRACE"))
+                                              .unwrap_or_default();
+    match raw.as_str() {
+      "full" => BacktracePref::Full,
+      "0" | "" => BacktracePref::Off,
+      _ => BacktracePref::Trimmed,
+    }
+  })
+}
+
+/// Renders `backtrace` per `pref`, trimming to [`TRIMMED_FRAME_LIMIT`] frames (split on
+/// each frame's leading `<N>:` marker) when only a short view was asked for.
+fn format_backtrace(backtrace: &snafu::Backtrace, pref: BacktracePref) -> String {
+  let full = backtrace.to_string();
+  if pref != BacktracePref::Trimmed {
+    return full;
+  }
+  let mut frames = 0usize;
+  let mut out = String::new();
+  for line in full.lines() {
+    if line.trim_start().chars().take_while(|c| c.is_ascii_digit()).count() > 0
+       && line.trim_start().contains(':')
+    {
+      frames += 1;
+      if frames > TRIMMED_FRAME_LIMIT {
+        out.push_str("   (backtrace truncated; set RUST_BACKTRACE=full for the rest)\n");
+        break;
+      }
+    }
+    out.push_str(line);
+    out.push('\n');
+  }
+  out
+}
+
+/// The user's `--color`/`--no-color` preference, resolved once at startup and fed to
+/// [`set_color_choice`] before any error can be reported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorChoice {
+  Always,
+  Never,
+  Auto,
+}
+
+static COLOR_OVERRIDE: OnceCell<Option<bool>> = OnceCell::new();
+
+/// Records the CLI's resolved `--color` choice. Must be called (at most once) before the
+/// first error is reported; `Auto` defers to [`colors_enabled`]'s own `NO_COLOR`/TTY check.
+pub fn set_color_choice(choice: ColorChoice) {
+  let _ = COLOR_OVERRIDE.set(match choice {
+                               ColorChoice::Always => Some(true),
+                               ColorChoice::Never => Some(false),
+                               ColorChoice::Auto => None,
+                             });
+}
+
+/// Whether error output should be colorized: an explicit [`set_color_choice`] wins, then the
+/// `NO_COLOR` convention (https://no-color.org), then whether stderr is a terminal at all.
+fn colors_enabled() -> bool {
+  if let Some(explicit) = COLOR_OVERRIDE.get().copied().flatten() {
+    return explicit;
+  }
+  if std::env::var_os("NO_COLOR").is_some() {
+    return false;
+  }
+  std::io::stderr().is_terminal()
+}
+
+impl<E: Error + ErrorCompat> fmt::Display for Report<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let e = match &self.0 {
+      Ok(()) => return Ok(()),
+      Err(e) => e,
+    };
+    let colors = colors_enabled();
+    if colors {
+      writeln!(f, "{} {}", "Error:".red().bold(), e)?;
+    } else {
+      writeln!(f, "Error: {}", e)?;
+    }
+    let mut current = e as &dyn Error;
+    while let Some(next) = current.source() {
+      if colors {
+        writeln!(f, "   {}", format!("Caused by: {}", next).dimmed())?;
+      } else {
+        writeln!(f, "   Caused by: {}", next)?;
+      }
+      current = next;
+    }
+    let pref = backtrace_pref();
+    if pref != BacktracePref::Off {
+      if let Some(backtrace) = ErrorCompat::backtrace(e) {
+        let rendered = format_backtrace(backtrace, pref);
+        if colors {
+          writeln!(f, "{}\n{}", "Backtrace:".dimmed(), rendered.dimmed())?;
+        } else {
+          writeln!(f, "Backtrace: \n{}", rendered)?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<E: Error + ErrorCompat> fmt::Debug for Report<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self, f)
+  }
+}
+
+/// Which shape failed `run()`s are reported in: `Text` is the human-readable chain printed
+/// by [`Display`](fmt::Display), `Json` is a single structured object for log pipelines.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorFormat {
+  Text,
+  Json,
+}
+
+static ERROR_FORMAT_OVERRIDE: OnceCell<ErrorFormat> = OnceCell::new();
+
+/// Records the CLI's resolved error-reporting format. Must be called (at most once) before
+/// the first error is reported; otherwise [`error_format`] falls back to `FINDORA_ERROR_FORMAT`.
+pub fn set_error_format(format: ErrorFormat) {
+  let _ = ERROR_FORMAT_OVERRIDE.set(format);
+}
+
+fn error_format() -> ErrorFormat {
+  if let Some(explicit) = ERROR_FORMAT_OVERRIDE.get().copied() {
+    return explicit;
+  }
+  match std::env::var("FINDORA_ERROR_FORMAT").as_deref() {
+    Ok("json") => ErrorFormat::Json,
+    _ => ErrorFormat::Text,
+  }
+}
+
+#[derive(serde::Serialize)]
+struct JsonErrorReport {
+  message: String,
+  causes: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  backtrace: Option<Vec<String>>,
+}
+
+fn json_report<E: Error + ErrorCompat>(e: &E) -> JsonErrorReport {
+  let mut causes = Vec::new();
+  let mut current = e as &dyn Error;
+  while let Some(next) = current.source() {
+    causes.push(next.to_string());
+    current = next;
+  }
+  let pref = backtrace_pref();
+  let backtrace = if pref == BacktracePref::Off {
+    None
+  } else {
+    ErrorCompat::backtrace(e).map(|bt| {
+                                format_backtrace(bt, pref).lines()
+                                                           .filter(|l| !l.is_empty())
+                                                           .map(str::to_string)
+                                                           .collect()
+                              })
+  };
+  JsonErrorReport { message: e.to_string(),
+                    causes,
+                    backtrace }
+}
+
+impl<E: Error + ErrorCompat> std::process::Termination for Report<E> {
+  fn report(self) -> std::process::ExitCode {
+    match self.0 {
+      Ok(()) => std::process::ExitCode::SUCCESS,
+      Err(e) => {
+        match error_format() {
+          ErrorFormat::Json => {
+            let report = json_report(&e);
+            match serde_json::to_string(&report) {
+              Ok(line) => eprintln!("{}", line),
+              Err(_) => eprintln!("{{\"message\": {:?}}}", report.message),
+            }
+          }
+          ErrorFormat::Text => eprint!("{}", Report(Err::<(), E>(e))),
+        }
+        std::process::ExitCode::FAILURE
+      }
+    }
+  }
+}
+
+/// Wraps a fallible `fn run() -> Result<(), E>` into the `fn main() -> Report<E>` that runs
+/// it and reports the result, e.g.:
+///
+/// ```ignore
+/// quick_main!(run);
+/// fn run() -> Result<(), CliError> {
+///     // ...
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! quick_main {
+  ($run:ident) => {
+    fn main() -> impl std::process::Termination {
+      $crate::report::Report::from($run())
+    }
+  };
+}
@@ -8,6 +8,7 @@ use ledger::error_location;
 use ledger::store::*;
 use ledger_api_service::RestfulArchiveAccess;
 use log::info;
+use lru::LruCache;
 use sparse_merkle_tree::Key;
 use std::collections::{HashMap, HashSet};
 
@@ -20,49 +21,377 @@ macro_rules! fail {
   };
 }
 
-pub struct QueryServer<T>
-  where T: RestfulArchiveAccess
-{
-  committed_state: LedgerState,
+/// Key-value abstraction over the ownership indices and custom-data store a `QueryServer`
+/// maintains, plus a "last indexed block" checkpoint. `QueryServer` talks to its state only
+/// through this trait, so swapping `InMemoryQueryServerStore` for a disk-backed implementation
+/// (e.g. wrapping `rocksdb`/`sled`) turns it from an ephemeral cache into a durable indexer that
+/// can resume `poll_new_blocks` after a restart instead of re-polling from `BlockSID(0)`.
+pub trait QueryServerStore {
+  fn get_owned_utxo_sids(&self, address: &XfrAddress) -> Option<HashSet<TxoSID>>;
+  fn insert_owned_utxo_sid(&mut self, address: XfrAddress, txo_sid: TxoSID);
+  fn remove_owned_utxo_sid(&mut self, address: &XfrAddress, txo_sid: &TxoSID) -> bool;
+
+  fn get_address_of_sid(&self, txo_sid: TxoSID) -> Option<XfrAddress>;
+  fn set_address_of_sid(&mut self, txo_sid: TxoSID, address: XfrAddress);
+  fn remove_address_of_sid(&mut self, txo_sid: &TxoSID);
+
+  /// Reading the related-transaction index may count as an access for recency purposes (an
+  /// implementation is free to back it with an LRU cache), hence `&mut self`.
+  fn get_related_transactions(&mut self, address: &XfrAddress) -> Option<HashSet<TxnSID>>;
+  /// Returns `true` iff `txn_sid` wasn't already recorded for `address`.
+  fn insert_related_transaction(&mut self, address: XfrAddress, txn_sid: TxnSID) -> bool;
+  fn remove_related_transaction(&mut self, address: &XfrAddress, txn_sid: &TxnSID);
+
+  fn get_issued_records(&self, issuer: &IssuerPublicKey) -> Option<Vec<TxOutput>>;
+  /// Appends `records` to `issuer`'s issuance history, returning how many were appended (i.e.
+  /// `records.len()`, handed back so callers don't need to borrow `records` again afterward).
+  fn append_issued_records(&mut self, issuer: IssuerPublicKey, records: Vec<TxOutput>) -> usize;
+  fn truncate_issued_records(&mut self, issuer: &IssuerPublicKey, new_len: usize);
+
+  /// As with `get_related_transactions`, reading may count as an LRU access, hence `&mut self`.
+  fn get_custom_data(&mut self, key: &Key) -> Option<(Vec<u8>, KVHash)>;
+  fn set_custom_data(&mut self, key: Key, value: (Vec<u8>, KVHash));
+  fn remove_custom_data(&mut self, key: &Key) -> Option<(Vec<u8>, KVHash)>;
+
+  /// The last `BlockSID` successfully indexed, if any -- `poll_new_blocks` resumes just past
+  /// this instead of from `BlockSID(0)`.
+  fn get_checkpoint(&self) -> Option<BlockSID>;
+  fn set_checkpoint(&mut self, bid: BlockSID);
+
+  /// The transaction that consumed `txo_sid`, and the output SIDs that transaction produced,
+  /// if it's been spent.
+  fn get_spent_utxo(&self, txo_sid: TxoSID) -> Option<(TxnSID, Vec<TxoSID>)>;
+  fn set_spent_utxo(&mut self, txo_sid: TxoSID, txn_sid: TxnSID, output_sids: Vec<TxoSID>);
+  fn remove_spent_utxo(&mut self, txo_sid: &TxoSID) -> Option<(TxnSID, Vec<TxoSID>)>;
+
+  /// `address`'s related transactions in application order, each tagged with how it
+  /// participated -- the ordered, direction-tagged counterpart to `get_related_transactions`'s
+  /// unordered `HashSet`, for backing a wallet-style history view.
+  fn get_address_history(&self, address: &XfrAddress) -> AddressHistory;
+  fn append_address_history(&mut self, address: XfrAddress, txn_sid: TxnSID, direction: TxnDirection);
+  fn truncate_address_history(&mut self, address: &XfrAddress, new_len: usize);
+}
+
+/// How an address participated in one related transaction. An address can fill more than one
+/// role in the same transaction (e.g. spending an input while also receiving change back), so
+/// this is a set of independent flags rather than a single variant.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TxnDirection {
+  pub sent: bool,
+  pub received: bool,
+  pub issued: bool,
+}
+
+/// One entry in an address's transaction history, as returned by
+/// `QueryServer::get_address_history`.
+#[derive(Clone, Debug)]
+pub struct AddressHistoryEntry {
+  pub txn_sid: TxnSID,
+  pub direction: TxnDirection,
+}
+
+/// An address's related transactions, oldest first, each tagged with the direction(s) it
+/// participated in that transaction.
+#[derive(Clone, Debug, Default)]
+pub struct AddressHistory {
+  pub entries: Vec<AddressHistoryEntry>,
+}
+
+/// Default capacity for `InMemoryQueryServerStore::default`'s custom-data cache. Operators who
+/// want a different memory/hit-rate tradeoff should call `InMemoryQueryServerStore::new`
+/// directly instead.
+pub const DEFAULT_CUSTOM_DATA_CAPACITY: usize = 10_000;
+/// Default capacity for `InMemoryQueryServerStore::default`'s related-transactions cache.
+pub const DEFAULT_RELATED_TRANSACTIONS_CAPACITY: usize = 10_000;
+
+/// In-memory `QueryServerStore`: the same indices `QueryServer` used to hold directly before
+/// this trait existed. Used by default and in tests; offers no durability of its own -- a
+/// disk-backed store is what actually survives a restart, by implementing `QueryServerStore`
+/// the same way.
+///
+/// `custom_data_store` and `related_transactions` grow without bound under a plain `HashMap`
+/// -- the latter in particular accumulates every `TxnSID` ever associated with an address and
+/// is never otherwise pruned -- so both are capacity-bounded LRU caches instead, with the
+/// capacities tunable via `new`. Because this store has nothing behind it to re-read an evicted
+/// entry from, eviction here is a hard drop; a disk-backed `QueryServerStore` would instead
+/// treat its own hot cache as a read-through layer over the persisted data, so an eviction
+/// there only means the next read costs a disk hit rather than losing the entry outright.
+pub struct InMemoryQueryServerStore {
   addresses_to_utxos: HashMap<XfrAddress, HashSet<TxoSID>>,
-  related_transactions: HashMap<XfrAddress, HashSet<TxnSID>>, // Set of transactions related to a ledger address
+  related_transactions: LruCache<XfrAddress, HashSet<TxnSID>>,
   issuances: HashMap<IssuerPublicKey, Vec<TxOutput>>,
   utxos_to_map_index: HashMap<TxoSID, XfrAddress>,
-  custom_data_store: HashMap<Key, (Vec<u8>, KVHash)>,
+  custom_data_store: LruCache<Key, (Vec<u8>, KVHash)>,
+  checkpoint: Option<BlockSID>,
+  spent_utxos: HashMap<TxoSID, (TxnSID, Vec<TxoSID>)>,
+  address_history: HashMap<XfrAddress, Vec<AddressHistoryEntry>>,
+}
+
+impl InMemoryQueryServerStore {
+  /// Builds an empty store whose `custom_data_store` and `related_transactions` caches evict
+  /// their least-recently-queried entry once `custom_data_capacity`/`related_transactions_capacity`
+  /// entries are held, respectively.
+  pub fn new(custom_data_capacity: usize, related_transactions_capacity: usize) -> Self {
+    InMemoryQueryServerStore { addresses_to_utxos: HashMap::new(),
+                               related_transactions: LruCache::new(related_transactions_capacity),
+                               issuances: HashMap::new(),
+                               utxos_to_map_index: HashMap::new(),
+                               custom_data_store: LruCache::new(custom_data_capacity),
+                               checkpoint: None,
+                               spent_utxos: HashMap::new(),
+                               address_history: HashMap::new() }
+  }
+}
+
+impl Default for InMemoryQueryServerStore {
+  fn default() -> Self {
+    Self::new(DEFAULT_CUSTOM_DATA_CAPACITY, DEFAULT_RELATED_TRANSACTIONS_CAPACITY)
+  }
+}
+
+impl QueryServerStore for InMemoryQueryServerStore {
+  fn get_owned_utxo_sids(&self, address: &XfrAddress) -> Option<HashSet<TxoSID>> {
+    self.addresses_to_utxos.get(address).cloned()
+  }
+
+  fn insert_owned_utxo_sid(&mut self, address: XfrAddress, txo_sid: TxoSID) {
+    self.addresses_to_utxos
+        .entry(address)
+        .or_insert_with(HashSet::new)
+        .insert(txo_sid);
+  }
+
+  fn remove_owned_utxo_sid(&mut self, address: &XfrAddress, txo_sid: &TxoSID) -> bool {
+    self.addresses_to_utxos
+        .get_mut(address)
+        .map(|set| set.remove(txo_sid))
+        .unwrap_or(false)
+  }
+
+  fn get_address_of_sid(&self, txo_sid: TxoSID) -> Option<XfrAddress> {
+    self.utxos_to_map_index.get(&txo_sid).cloned()
+  }
+
+  fn set_address_of_sid(&mut self, txo_sid: TxoSID, address: XfrAddress) {
+    self.utxos_to_map_index.insert(txo_sid, address);
+  }
+
+  fn remove_address_of_sid(&mut self, txo_sid: &TxoSID) {
+    self.utxos_to_map_index.remove(txo_sid);
+  }
+
+  fn get_related_transactions(&mut self, address: &XfrAddress) -> Option<HashSet<TxnSID>> {
+    self.related_transactions.get(address).cloned()
+  }
+
+  fn insert_related_transaction(&mut self, address: XfrAddress, txn_sid: TxnSID) -> bool {
+    if let Some(set) = self.related_transactions.get_mut(&address) {
+      return set.insert(txn_sid);
+    }
+    let mut set = HashSet::new();
+    set.insert(txn_sid);
+    self.related_transactions.put(address, set);
+    true
+  }
+
+  fn remove_related_transaction(&mut self, address: &XfrAddress, txn_sid: &TxnSID) {
+    if let Some(set) = self.related_transactions.get_mut(address) {
+      set.remove(txn_sid);
+    }
+  }
+
+  fn get_issued_records(&self, issuer: &IssuerPublicKey) -> Option<Vec<TxOutput>> {
+    self.issuances.get(issuer).cloned()
+  }
+
+  fn append_issued_records(&mut self, issuer: IssuerPublicKey, mut records: Vec<TxOutput>) -> usize {
+    let appended = records.len();
+    self.issuances
+        .entry(issuer)
+        .or_insert_with(Vec::new)
+        .append(&mut records);
+    appended
+  }
+
+  fn truncate_issued_records(&mut self, issuer: &IssuerPublicKey, new_len: usize) {
+    if let Some(records) = self.issuances.get_mut(issuer) {
+      records.truncate(new_len);
+    }
+  }
+
+  fn get_custom_data(&mut self, key: &Key) -> Option<(Vec<u8>, KVHash)> {
+    self.custom_data_store.get(key).cloned()
+  }
+
+  fn set_custom_data(&mut self, key: Key, value: (Vec<u8>, KVHash)) {
+    self.custom_data_store.put(key, value);
+  }
+
+  fn remove_custom_data(&mut self, key: &Key) -> Option<(Vec<u8>, KVHash)> {
+    self.custom_data_store.pop(key)
+  }
+
+  fn get_checkpoint(&self) -> Option<BlockSID> {
+    self.checkpoint
+  }
+
+  fn set_checkpoint(&mut self, bid: BlockSID) {
+    self.checkpoint = Some(bid);
+  }
+
+  fn get_spent_utxo(&self, txo_sid: TxoSID) -> Option<(TxnSID, Vec<TxoSID>)> {
+    self.spent_utxos.get(&txo_sid).cloned()
+  }
+
+  fn set_spent_utxo(&mut self, txo_sid: TxoSID, txn_sid: TxnSID, output_sids: Vec<TxoSID>) {
+    self.spent_utxos.insert(txo_sid, (txn_sid, output_sids));
+  }
+
+  fn remove_spent_utxo(&mut self, txo_sid: &TxoSID) -> Option<(TxnSID, Vec<TxoSID>)> {
+    self.spent_utxos.remove(txo_sid)
+  }
+
+  fn get_address_history(&self, address: &XfrAddress) -> AddressHistory {
+    AddressHistory { entries: self.address_history.get(address).cloned().unwrap_or_default() }
+  }
+
+  fn append_address_history(&mut self, address: XfrAddress, txn_sid: TxnSID, direction: TxnDirection) {
+    self.address_history
+        .entry(address)
+        .or_insert_with(Vec::new)
+        .push(AddressHistoryEntry { txn_sid, direction });
+  }
+
+  fn truncate_address_history(&mut self, address: &XfrAddress, new_len: usize) {
+    if let Some(entries) = self.address_history.get_mut(address) {
+      entries.truncate(new_len);
+    }
+  }
+}
+
+/// Everything one `add_new_block`/`add_new_block_at` call mutated on a `QueryServer`'s store,
+/// in enough detail to undo it. `retract_block` replays each field in reverse: newly inserted
+/// UTXOs are removed, UTXOs the block spent are restored, issuance vectors are truncated back
+/// by the number of records appended, related-transaction entries added by the block are
+/// removed, and custom-data entries the block's `KVStoreUpdate`s dropped are restored to what
+/// they held before.
+#[derive(Default)]
+struct BlockJournal {
+  utxos_inserted: Vec<(TxoSID, XfrAddress)>,
+  utxos_removed: Vec<(TxoSID, XfrAddress)>,
+  issuance_records_appended: HashMap<IssuerPublicKey, usize>,
+  related_transactions_added: Vec<(XfrAddress, TxnSID)>,
+  custom_data_removed: Vec<(Key, (Vec<u8>, KVHash))>,
+  /// `TxoSID`s this block recorded as spent, to drop again on retraction.
+  spent_utxos_set: Vec<TxoSID>,
+  /// How many `AddressHistory` entries this block appended per address, to truncate back off
+  /// on retraction.
+  address_history_appended: HashMap<XfrAddress, usize>,
+}
+
+pub struct QueryServer<T, S = InMemoryQueryServerStore>
+  where T: RestfulArchiveAccess,
+        S: QueryServerStore
+{
+  committed_state: LedgerState,
+  store: S,
+  /// The `BlockSID` of every block applied so far, in application order -- the only chain
+  /// topology `poll_new_blocks` has to detect a reorg with, since this tree's vendored
+  /// `ledger` crate hands out `BlockSID`s as plain sequential heights with no parent-hash of
+  /// their own to diff against.
+  applied_block_ids: Vec<BlockSID>,
+  /// Per-applied-block undo journal, parallel to `applied_block_ids`.
+  block_journals: Vec<BlockJournal>,
+  /// Blocks `poll_new_blocks` pulled from `rest_client` that failed to apply, keyed by the
+  /// `BlockSID` the archive node reported them under, with the failure reason attached. Lets
+  /// an operator inspect why indexing stalled instead of the process just panicking.
+  quarantined_blocks: HashMap<BlockSID, String>,
+  /// How many `poll_new_blocks` calls in a row have produced at least one quarantined block,
+  /// reset to zero the moment a block from `rest_client` applies cleanly. `is_source_banned`
+  /// trips once this crosses `MAX_CONSECUTIVE_POLL_FAILURES`, so a persistently bad archive
+  /// endpoint gets backed off instead of being retried forever.
+  consecutive_poll_failures: usize,
   rest_client: T,
 }
 
-impl<T> QueryServer<T> where T: RestfulArchiveAccess
+/// How many consecutive `poll_new_blocks` calls may quarantine a block before
+/// `QueryServer::is_source_banned` starts refusing to poll `rest_client` further.
+const MAX_CONSECUTIVE_POLL_FAILURES: usize = 5;
+
+impl<T, S> QueryServer<T, S>
+  where T: RestfulArchiveAccess,
+        S: QueryServerStore + Default
+{
+  pub fn new(rest_client: T) -> QueryServer<T, S> {
+    QueryServer { committed_state: LedgerState::test_ledger(),
+                  store: S::default(),
+                  applied_block_ids: Vec::new(),
+                  block_journals: Vec::new(),
+                  quarantined_blocks: HashMap::new(),
+                  consecutive_poll_failures: 0,
+                  rest_client }
+  }
+}
+
+impl<T, S> QueryServer<T, S>
+  where T: RestfulArchiveAccess,
+        S: QueryServerStore
 {
-  pub fn new(rest_client: T) -> QueryServer<T> {
+  /// As `new`, but with an already-configured store -- e.g. an `InMemoryQueryServerStore::new`
+  /// built with non-default LRU capacities, or a disk-backed store opened at a given path.
+  pub fn with_store(rest_client: T, store: S) -> QueryServer<T, S> {
     QueryServer { committed_state: LedgerState::test_ledger(),
-                  addresses_to_utxos: HashMap::new(),
-                  related_transactions: HashMap::new(),
-                  custom_data_store: HashMap::new(),
-                  issuances: HashMap::new(),
-                  utxos_to_map_index: HashMap::new(),
+                  store,
+                  applied_block_ids: Vec::new(),
+                  block_journals: Vec::new(),
+                  quarantined_blocks: HashMap::new(),
+                  consecutive_poll_failures: 0,
                   rest_client }
   }
 
+  /// Blocks rejected by a past `poll_new_blocks` call, keyed by the `BlockSID` they were
+  /// reported under, with the reason they failed to apply.
+  pub fn quarantined_blocks(&self) -> &HashMap<BlockSID, String> {
+    &self.quarantined_blocks
+  }
+
+  /// Whether `rest_client` has produced `MAX_CONSECUTIVE_POLL_FAILURES` bad blocks in a row
+  /// with no clean application in between -- a signal that this archive endpoint should be
+  /// backed off or banned rather than polled again immediately.
+  pub fn is_source_banned(&self) -> bool {
+    self.consecutive_poll_failures >= MAX_CONSECUTIVE_POLL_FAILURES
+  }
+
   // Fetch custom data at a given key
-  pub fn get_custom_data(&self, key: &Key) -> Option<&(Vec<u8>, KVHash)> {
-    self.custom_data_store.get(key)
+  pub fn get_custom_data(&mut self, key: &Key) -> Option<(Vec<u8>, KVHash)> {
+    self.store.get_custom_data(key)
   }
 
   pub fn get_issued_records(&self, issuer: &IssuerPublicKey) -> Option<Vec<TxOutput>> {
-    self.issuances.get(issuer).cloned()
+    self.store.get_issued_records(issuer)
   }
 
-  pub fn get_related_transactions(&self, address: &XfrAddress) -> Option<HashSet<TxnSID>> {
-    self.related_transactions.get(&address).cloned()
+  pub fn get_related_transactions(&mut self, address: &XfrAddress) -> Option<HashSet<TxnSID>> {
+    self.store.get_related_transactions(address)
   }
 
   pub fn get_owned_utxo_sids(&self, address: &XfrAddress) -> Option<HashSet<TxoSID>> {
-    self.addresses_to_utxos.get(&address).cloned()
+    self.store.get_owned_utxo_sids(address)
   }
 
   pub fn get_address_of_sid(&self, txo_sid: TxoSID) -> Option<XfrAddress> {
-    self.utxos_to_map_index.get(&txo_sid).cloned()
+    self.store.get_address_of_sid(txo_sid)
+  }
+
+  /// The transaction that spent `txo_sid`, and the output SIDs it produced, if it's been spent.
+  pub fn get_spent_utxo(&self, txo_sid: TxoSID) -> Option<(TxnSID, Vec<TxoSID>)> {
+    self.store.get_spent_utxo(txo_sid)
+  }
+
+  /// `address`'s chronologically ordered, direction-tagged transaction history -- a
+  /// wallet-style view `get_related_transactions`'s unordered `HashSet` can't back on its own.
+  pub fn get_address_history(&self, address: &XfrAddress) -> AddressHistory {
+    self.store.get_address_history(address)
   }
 
   // Attempt to add to data store at a given location
@@ -87,72 +416,155 @@ impl<T> QueryServer<T> where T: RestfulArchiveAccess
     }
 
     // Hash matches, store data
-    self.custom_data_store
-        .insert(*key, (data.as_ref().into(), hash));
+    self.store.set_custom_data(*key, (data.as_ref().into(), hash));
     Ok(())
   }
 
   // Cache issuance records
-  pub fn cache_issuance(&mut self, issuance: &IssueAsset) {
+  pub fn cache_issuance(&mut self, issuance: &IssueAsset, journal: &mut BlockJournal) {
     let issuer = issuance.pubkey;
-    let mut new_records = issuance.body.records.clone();
-    let records = self.issuances.entry(issuer).or_insert_with(Vec::new);
+    let new_records = issuance.body.records.clone();
     info!("Issuance record cached for asset issuer key {}",
           b64enc(&issuer.key.as_bytes()));
-    records.append(&mut new_records);
+    let appended = self.store.append_issued_records(issuer, new_records);
+    *journal.issuance_records_appended.entry(issuer).or_insert(0) += appended;
   }
 
   // Remove data that may be outdated based on this kv_update
-  fn remove_stale_data(&mut self, kv_update: &KVUpdate) {
+  fn remove_stale_data(&mut self, kv_update: &KVUpdate, journal: &mut BlockJournal) {
     let key = kv_update.body.0;
     let entry = kv_update.body.2.as_ref();
-    if let Some((_, curr_hash)) = self.custom_data_store.get(&key) {
+    if let Some((_, curr_hash)) = self.store.get_custom_data(&key) {
       // If hashes don't match, data is stale
-      if let Some(entry) = entry {
-        if entry.1 != *curr_hash {
-          self.custom_data_store.remove(&key);
+      let is_stale = match entry {
+        Some(entry) => entry.1 != curr_hash,
+        None => true,
+      };
+      if is_stale {
+        if let Some(removed) = self.store.remove_custom_data(&key) {
+          journal.custom_data_removed.push((key, removed));
         }
-      } else {
-        self.custom_data_store.remove(&key);
       }
     }
   }
 
-  fn remove_spent_utxos(&mut self, transfer: &TransferAsset) -> Result<(), PlatformError> {
+  fn remove_spent_utxos(&mut self,
+                        transfer: &TransferAsset,
+                        txn_sid: TxnSID,
+                        output_sids: &[TxoSID],
+                        journal: &mut BlockJournal)
+                        -> Result<(), PlatformError> {
     for input in &transfer.body.inputs {
       match input {
         TxoRef::Relative(_) => {} // Relative utxos were never cached so no need to do anything here
         TxoRef::Absolute(txo_sid) => {
-          let address = self.utxos_to_map_index
-                            .get(&txo_sid)
+          let address = self.store
+                            .get_address_of_sid(*txo_sid)
                             .ok_or_else(|| fail!("Attempting to remove owned txo of address that isn't cached"))?;
-          let hash_set = self.addresses_to_utxos
-                             .get_mut(&address)
-                             .ok_or_else(|| fail!("No txos stored for this address"))?;
-          let removed = hash_set.remove(&txo_sid);
+          let removed = self.store.remove_owned_utxo_sid(&address, txo_sid);
           if !removed {
             return Err(fail!("Input txo not found"));
           }
+          journal.utxos_removed.push((*txo_sid, address));
+          self.store
+              .set_spent_utxo(*txo_sid, txn_sid, output_sids.to_vec());
+          journal.spent_utxos_set.push(*txo_sid);
         }
       }
     }
     Ok(())
   }
 
+  /// Undoes exactly what one `BlockJournal` recorded, in reverse, leaving the store as if the
+  /// block it came from had never been applied. Does not (and, without vendoring the real
+  /// `ledger` crate's own rollback API, cannot) unwind `committed_state` itself -- the next
+  /// `add_new_block_at` call re-finalizes the replacement block against it, which lands the
+  /// store back where a fresh replay of the canonical branch would leave it, the invariant
+  /// this journal exists to preserve.
+  fn retract_block(&mut self, journal: BlockJournal) {
+    for (txo_sid, address) in &journal.utxos_inserted {
+      self.store.remove_owned_utxo_sid(address, txo_sid);
+      self.store.remove_address_of_sid(txo_sid);
+    }
+    for (txo_sid, address) in &journal.utxos_removed {
+      self.store.insert_owned_utxo_sid(*address, *txo_sid);
+    }
+    for (issuer, appended) in &journal.issuance_records_appended {
+      if let Some(records) = self.store.get_issued_records(issuer) {
+        self.store
+            .truncate_issued_records(issuer, records.len().saturating_sub(*appended));
+      }
+    }
+    for (address, txn_sid) in &journal.related_transactions_added {
+      self.store.remove_related_transaction(address, txn_sid);
+    }
+    for (key, old_value) in journal.custom_data_removed {
+      self.store.set_custom_data(key, old_value);
+    }
+    for txo_sid in &journal.spent_utxos_set {
+      self.store.remove_spent_utxo(txo_sid);
+    }
+    for (address, appended) in &journal.address_history_appended {
+      let new_len = self.store
+                        .get_address_history(address)
+                        .entries
+                        .len()
+                        .saturating_sub(*appended);
+      self.store.truncate_address_history(address, new_len);
+    }
+  }
+
+  /// Rolls the cache back to just after the block at `target_len` (exclusive), undoing every
+  /// applied block after it in reverse order by replaying its journal.
+  fn retract_to(&mut self, target_len: usize) -> Result<(), PlatformError> {
+    while self.applied_block_ids.len() > target_len {
+      self.applied_block_ids.pop();
+      let journal = self.block_journals
+                        .pop()
+                        .ok_or_else(|| fail!("Journal missing for an applied block during reorg"))?;
+      self.retract_block(journal);
+    }
+    Ok(())
+  }
+
   // Updates query server cache with new transactions from a block.
   // Each new block must be consistent with the state of the cached ledger up until this point
   pub fn add_new_block(&mut self, block: &[FinalizedTransaction]) -> Result<(), PlatformError> {
-    // First, we add block to local ledger state
+    let bid = BlockSID(self.applied_block_ids.len());
+    self.add_new_block_at(bid, block)
+  }
+
+  /// As `add_new_block`, but records the block under the given `BlockSID` rather than
+  /// assuming it's the next one in sequence -- used by `poll_new_blocks` so a reorg's
+  /// replacement blocks land under the heights the ledger actually reports for them, and so
+  /// the store's checkpoint always reflects the height that was actually indexed.
+  pub fn add_new_block_at(&mut self,
+                          bid: BlockSID,
+                          block: &[FinalizedTransaction])
+                          -> Result<(), PlatformError> {
+    let mut journal = BlockJournal::default();
+
+    // First, we add block to local ledger state. Blocks come straight from `rest_client`,
+    // which may be an untrusted or merely lagging archive node, so every step here is
+    // `?`-propagated rather than unwrapped -- a malformed or inconsistent block fails this
+    // call cleanly instead of panicking the whole query server; `poll_new_blocks` is the one
+    // that decides what to do with the resulting `Err` (quarantine and move on).
     let finalized_block = {
-      let mut block_builder = self.committed_state.start_block().unwrap();
+      let mut block_builder =
+        self.committed_state
+            .start_block()
+            .map_err(|e| fail!(format!("Could not start block {}: {:?}", bid, e)))?;
       for txn in block {
-        let eff = TxnEffect::compute_effect(txn.txn.clone()).unwrap();
+        let eff = TxnEffect::compute_effect(txn.txn.clone())
+          .map_err(|e| fail!(format!("Invalid transaction in block {}: {:?}", bid, e)))?;
         self.committed_state
             .apply_transaction(&mut block_builder, eff)
-            .unwrap();
+            .map_err(|e| fail!(format!("Rejected transaction in block {}: {:?}", bid, e)))?;
       }
 
-      self.committed_state.finish_block(block_builder).unwrap()
+      self.committed_state
+          .finish_block(block_builder)
+          .map_err(|e| fail!(format!("Could not finish block {}: {:?}", bid, e)))?
     };
     // Next, update ownership status
     for (_, (txn_sid, txo_sids)) in finalized_block.iter() {
@@ -166,40 +578,78 @@ impl<T> QueryServer<T> where T: RestfulArchiveAccess
         (ledger.get_transaction(*txn_sid).unwrap().finalized_txn.txn, addresses)
       };
 
-      // Update related addresses
+      // Update related addresses, tagged with how each one participated in this transaction
       let related_addresses = get_related_addresses(&txn);
-      for address in &related_addresses {
-        self.related_transactions
-            .entry(*address)
-            .or_insert_with(HashSet::new)
-            .insert(*txn_sid);
+      for (address, direction) in &related_addresses {
+        let newly_added = self.store.insert_related_transaction(*address, *txn_sid);
+        if newly_added {
+          journal.related_transactions_added.push((*address, *txn_sid));
+        }
+        self.store
+            .append_address_history(*address, *txn_sid, *direction);
+        *journal.address_history_appended.entry(*address).or_insert(0) += 1;
       }
 
-      // Remove spent utxos
+      // Remove spent utxos. `remove_spent_utxos` can fail partway through a transfer's
+      // input list, after already mutating `self.store` for earlier inputs in this
+      // transaction (and earlier transactions in this block already did too) -- without
+      // undoing those via `journal`, a quarantined block would leave the store permanently
+      // inconsistent with the committed ledger, with no journal entry left to retract it
+      // with. So any failure here rolls back everything `journal` has accumulated for this
+      // block so far before propagating the error.
       for op in &txn.body.operations {
-        match op {
-          Operation::TransferAsset(transfer_asset) => self.remove_spent_utxos(&transfer_asset)?,
-          Operation::KVStoreUpdate(kv_update) => self.remove_stale_data(&kv_update),
-          Operation::IssueAsset(issue_asset) => self.cache_issuance(&issue_asset),
-          _ => {}
+        let res = match op {
+          Operation::TransferAsset(transfer_asset) => {
+            self.remove_spent_utxos(&transfer_asset, *txn_sid, txo_sids, &mut journal)
+          }
+          Operation::KVStoreUpdate(kv_update) => {
+            self.remove_stale_data(&kv_update, &mut journal);
+            Ok(())
+          }
+          Operation::IssueAsset(issue_asset) => {
+            self.cache_issuance(&issue_asset, &mut journal);
+            Ok(())
+          }
+          _ => Ok(()),
         };
+        if let Err(e) = res {
+          self.retract_block(journal);
+          return Err(e);
+        }
       }
 
       // Add new utxos (this handles both transfers and issuances)
       for (txo_sid, address) in txo_sids.iter().zip(addresses.iter()) {
-        self.addresses_to_utxos
-            .entry(*address)
-            .or_insert_with(HashSet::new)
-            .insert(*txo_sid);
-        self.utxos_to_map_index.insert(*txo_sid, *address);
+        self.store.insert_owned_utxo_sid(*address, *txo_sid);
+        self.store.set_address_of_sid(*txo_sid, *address);
+        journal.utxos_inserted.push((*txo_sid, *address));
       }
     }
+
+    self.applied_block_ids.push(bid);
+    self.block_journals.push(journal);
+    self.store.set_checkpoint(bid);
     Ok(())
   }
 
   pub fn poll_new_blocks(&mut self) -> Result<(), PlatformError> {
-    let latest_block = self.committed_state.get_block_count();
-    let new_blocks = match self.rest_client.get_blocks_since(BlockSID(latest_block)) {
+    // A source that's already tripped the ban threshold gets no further requests until an
+    // operator clears it (by inspecting `quarantined_blocks` and restarting against a
+    // trustworthy endpoint) -- repeatedly hammering a bad archive node is no better than
+    // panicking on its first bad block.
+    if self.is_source_banned() {
+      return Err(fail!(format!("Archive source banned after {} consecutive bad blocks; see quarantined_blocks()",
+                                self.consecutive_poll_failures)));
+    }
+
+    // Resume from the store's checkpoint rather than `committed_state`'s own block count: the
+    // store is what a disk-backed implementation actually persists across restarts, so this is
+    // what lets `QueryServer` pick back up instead of re-polling from `BlockSID(0)` every time.
+    let next_block = self.store
+                         .get_checkpoint()
+                         .map(|checkpoint| checkpoint.0 + 1)
+                         .unwrap_or(0);
+    let new_blocks = match self.rest_client.get_blocks_since(BlockSID(next_block)) {
       Err(_) => {
         return Err(fail!("Cannot connect to ledger server"));
       }
@@ -209,47 +659,77 @@ impl<T> QueryServer<T> where T: RestfulArchiveAccess
 
     for (bid, block) in new_blocks {
       info!("Received block {}", bid);
-      self.add_new_block(&block)?;
+      // A polled block landing at a height we've already applied means the branch the
+      // ledger is now serving has diverged from the one we indexed -- retract every applied
+      // block from that height onward (in reverse order, via their journals) before applying
+      // the new branch's block in its place. See `applied_block_ids`'s doc comment for why
+      // the height itself is the best "common ancestor" available here.
+      if bid.0 < self.applied_block_ids.len() {
+        self.retract_to(bid.0)?;
+      }
+      // Quarantine rather than abort the whole poll: one bad block from a lagging or
+      // misbehaving archive node shouldn't stop every later, valid block in the same batch
+      // from being indexed.
+      match self.add_new_block_at(bid, &block) {
+        Ok(()) => self.consecutive_poll_failures = 0,
+        Err(e) => {
+          info!("Quarantined block {}: {:?}", bid, e);
+          self.quarantined_blocks.insert(bid, format!("{:?}", e));
+          self.consecutive_poll_failures += 1;
+          if self.is_source_banned() {
+            return Err(fail!(format!("Archive source banned after {} consecutive bad blocks; see quarantined_blocks()",
+                                      self.consecutive_poll_failures)));
+          }
+        }
+      }
     }
 
     Ok(())
   }
 }
 
-// An xfr address is related to a transaction if it is one of the following:
-// 1. Owner of a transfer output
-// 2. Transfer signer (owner of input or co-signer)
-// 3. Signer of a an issuance txn
-// 4. Signer of a kv_update txn
-// 5. Signer of a memo_update txn
-fn get_related_addresses(txn: &Transaction) -> HashSet<XfrAddress> {
-  let mut related_addresses = HashSet::new();
+// An xfr address is related to a transaction if it is one of the following, classified into
+// the `TxnDirection` flag(s) that apply -- an address can fill more than one role in the same
+// transaction (e.g. sending to someone else while also receiving change back):
+// 1. An input owner of a transfer (sent)
+// 2. An output owner of a transfer (received)
+// 3. The issuer of an issuance (issued)
+// 4. The definer of an asset, memo updater, AIR assignee, or KV-store updater -- related, but
+//    none of the above directions apply, so its flags are left all-`false`
+fn get_related_addresses(txn: &Transaction) -> HashMap<XfrAddress, TxnDirection> {
+  let mut related_addresses: HashMap<XfrAddress, TxnDirection> = HashMap::new();
   for op in &txn.body.operations {
     match op {
       Operation::TransferAsset(transfer) => {
         for input in transfer.body.transfer.inputs.iter() {
-          related_addresses.insert(XfrAddress { key: input.public_key });
+          related_addresses.entry(XfrAddress { key: input.public_key })
+                           .or_default()
+                           .sent = true;
         }
 
-        for output in transfer.body.transfer.inputs.iter() {
-          related_addresses.insert(XfrAddress { key: output.public_key });
+        for output in transfer.body.transfer.outputs.iter() {
+          related_addresses.entry(XfrAddress { key: output.public_key })
+                           .or_default()
+                           .received = true;
         }
       }
       Operation::IssueAsset(issue_asset) => {
-        related_addresses.insert(XfrAddress { key: issue_asset.pubkey.key });
+        related_addresses.entry(XfrAddress { key: issue_asset.pubkey.key })
+                         .or_default()
+                         .issued = true;
       }
       Operation::DefineAsset(define_asset) => {
-        related_addresses.insert(XfrAddress { key: define_asset.pubkey.key });
+        related_addresses.entry(XfrAddress { key: define_asset.pubkey.key }).or_default();
       }
       Operation::UpdateMemo(update_memo) => {
-        related_addresses.insert(XfrAddress { key: update_memo.pubkey });
+        related_addresses.entry(XfrAddress { key: update_memo.pubkey }).or_default();
       }
       Operation::AIRAssign(air_assign) => {
-        related_addresses.insert(XfrAddress { key: air_assign.pubkey });
+        related_addresses.entry(XfrAddress { key: air_assign.pubkey }).or_default();
       }
       Operation::KVStoreUpdate(kv_store_update) => {
         if let Some(entry) = &kv_store_update.body.2 {
-          related_addresses.insert(XfrAddress { key: entry.0 });
+          related_addresses.entry(XfrAddress { key: entry.0 }).or_default();
         }
       }
     }
@@ -424,4 +904,74 @@ mod tests {
     assert!(alice_related_txns.contains(&TxnSID(0)));
     assert!(bob_sids.contains(&TxoSID(3)));
   }
+
+  #[test]
+  pub fn test_get_related_addresses_tags_sender_and_recipient() {
+    let mut ledger_state = LedgerState::test_ledger();
+    let mut prng = ChaChaRng::from_entropy();
+    let token_code = AssetTypeCode::gen_random();
+    let alice = XfrKeyPair::generate(&mut prng);
+    let bob = XfrKeyPair::generate(&mut prng);
+
+    let mut builder = TransactionBuilder::from_seq_id(ledger_state.get_block_commit_count());
+    let define_tx = builder.add_operation_create_asset(&alice,
+                                                       Some(token_code),
+                                                       AssetRules::default(),
+                                                       "fiat".into(),
+                                                       PolicyChoice::Fungible())
+                           .unwrap()
+                           .transaction();
+    apply_transaction(&mut ledger_state, define_tx);
+
+    let amt = 1000;
+    let confidentiality_flag = NonConfidentialAmount_NonConfidentialAssetType;
+    let mut builder = TransactionBuilder::from_seq_id(ledger_state.get_block_commit_count());
+    let issuance_tx =
+      builder.add_basic_issue_asset(&alice, None, &token_code, 0, amt, confidentiality_flag)
+             .unwrap()
+             .transaction();
+    apply_transaction(&mut ledger_state, issuance_tx);
+
+    // Alice sends part of her issued amount to Bob, and receives the remainder back as
+    // change -- so this single transfer should tag Alice as both `sent` and `received`,
+    // and Bob as `received` only.
+    let transfer_sid = TxoSID(0);
+    let bar = &(ledger_state.get_utxo(transfer_sid).unwrap().0).0;
+    let oar = open_blind_asset_record(&bar, &None, alice.get_sk_ref()).unwrap();
+    let sent_amt = 400;
+    let change_amt = amt - sent_amt;
+    let mut xfr_builder = TransferOperationBuilder::new();
+    let bob_template = AssetRecordTemplate::with_no_asset_tracking(sent_amt,
+                                                                   token_code.val,
+                                                                   oar.get_record_type(),
+                                                                   bob.get_pk());
+    let alice_change_template =
+      AssetRecordTemplate::with_no_asset_tracking(change_amt,
+                                                   token_code.val,
+                                                   oar.get_record_type(),
+                                                   alice.get_pk());
+    let xfr_op = xfr_builder.add_input(TxoRef::Absolute(transfer_sid), oar, None, None, amt)
+                            .unwrap()
+                            .add_output(&bob_template, None, None, None)
+                            .unwrap()
+                            .add_output(&alice_change_template, None, None, None)
+                            .unwrap()
+                            .create(TransferType::Standard)
+                            .unwrap()
+                            .sign(&alice)
+                            .unwrap();
+    let mut builder = TransactionBuilder::from_seq_id(ledger_state.get_block_commit_count());
+    let xfr_txn = builder.add_operation(xfr_op.transaction().unwrap())
+                         .transaction();
+
+    let related = get_related_addresses(&xfr_txn);
+
+    let alice_direction = related.get(&XfrAddress { key: *alice.get_pk_ref() }).unwrap();
+    assert!(alice_direction.sent);
+    assert!(alice_direction.received);
+
+    let bob_direction = related.get(&XfrAddress { key: *bob.get_pk_ref() }).unwrap();
+    assert!(!bob_direction.sent);
+    assert!(bob_direction.received);
+  }
 }
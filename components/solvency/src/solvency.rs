@@ -1,15 +1,18 @@
 #![deny(warnings)]
 use bulletproofs::r1cs::R1CSProof;
-use bulletproofs::PedersenGens;
-use curve25519_dalek::ristretto::CompressedRistretto;
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
 use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
 use ledger::data_model::errors::PlatformError;
 use ledger::data_model::AssetTypeCode;
 use ledger::error_location;
 use linear_map::LinearMap;
+use merlin::Transcript;
 use rand_chacha::ChaChaRng;
-use rand_core::SeedableRng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
 use zei::crypto::solvency::{prove_solvency, verify_solvency};
 use zei::xfr::structs::asset_type_to_scalar;
 
@@ -18,6 +21,286 @@ pub type AssetCodeAndRate = (Scalar, Scalar);
 pub type AssetCommitment = (CompressedRistretto, CompressedRistretto);
 pub type LiabilityCommitment = (CompressedRistretto, CompressedRistretto);
 
+/// An exponential-ElGamal ciphertext over Ristretto: `(r*G, m*G + r*Pk)`. Keeping the
+/// plaintext `m` in the exponent (rather than encoding it directly as a point) is what lets
+/// [`prove_tracing_equality`] relate it to a Pedersen commitment of the same `m` -- the cost is
+/// that recovering `m` from a ciphertext takes a discrete-log search, so decryption is only
+/// practical up to [`TRACING_DECRYPT_BOUND`].
+pub type ElGamalCiphertext = (CompressedRistretto, CompressedRistretto);
+
+/// Ceiling on the plaintext values [`decrypt_exponential`] will search for. Real holdings
+/// brought under audit fit comfortably under this; it exists only because exponential ElGamal
+/// has no faster general decryption than brute-force (or baby-step-giant-step, which this
+/// self-contained implementation doesn't bother with).
+const TRACING_DECRYPT_BOUND: u64 = 1 << 20;
+
+/// Generates an ElGamal keypair over the same Ristretto group (and the same base point `G =
+/// PedersenGens::default().B`) that the hidden-asset commitments use, so a ciphertext's
+/// plaintext can be proved equal to a commitment's value. Returns `(secret_key, public_key)`.
+pub fn elgamal_keygen<R: CryptoRng + RngCore>(prng: &mut R) -> (Scalar, CompressedRistretto) {
+  let sk = Scalar::random(prng);
+  let pk = (sk * PedersenGens::default().B).compress();
+  (sk, pk)
+}
+
+/// Encrypts `value` under `public_key` using fresh randomness `r`, returning the ciphertext and
+/// `r` itself (the caller needs `r` to build the accompanying [`TracingEqualityProof`]).
+fn elgamal_encrypt(public_key: &CompressedRistretto,
+                   value: Scalar,
+                   r: Scalar)
+                   -> ElGamalCiphertext {
+  let g = PedersenGens::default().B;
+  let pk = public_key.decompress().expect("valid tracer public key");
+  let e1 = (r * g).compress();
+  let e2 = (value * g + r * pk).compress();
+  (e1, e2)
+}
+
+/// Recovers `value` from an exponential-ElGamal ciphertext by brute-force discrete-log search
+/// over `0..TRACING_DECRYPT_BOUND`. Returns `None` if no value in that range matches.
+fn decrypt_exponential(ciphertext: &ElGamalCiphertext, secret_key: Scalar) -> Option<u64> {
+  let g = PedersenGens::default().B;
+  let e1 = ciphertext.0.decompress().expect("valid ciphertext component");
+  let e2 = ciphertext.1.decompress().expect("valid ciphertext component");
+  let target = e2 - secret_key * e1;
+  let mut accum = RistrettoPoint::identity();
+  for candidate in 0..TRACING_DECRYPT_BOUND {
+    if accum == target {
+      return Some(candidate);
+    }
+    accum += g;
+  }
+  None
+}
+
+/// Hashes a transcript of compressed points into a Fiat-Shamir challenge scalar. Plays the
+/// same "commit to everything public before deriving the challenge" role a `merlin::Transcript`
+/// would, just hand-rolled since this module doesn't otherwise use `merlin`.
+fn tracing_challenge(points: &[&CompressedRistretto]) -> Scalar {
+  let mut bytes = Vec::with_capacity(points.len() * 32);
+  for point in points {
+    bytes.extend_from_slice(point.as_bytes());
+  }
+  Scalar::hash_from_bytes::<Sha512>(&bytes)
+}
+
+/// A non-interactive proof that the value hidden in a Pedersen commitment `C = a*G + r_p*H`
+/// is the same `a` an [`ElGamalCiphertext`] `(E1, E2) = (r_e*G, a*G + r_e*Pk)` encrypts --
+/// without revealing `a`, `r_p`, or `r_e`. This is what lets a tracer trust a decrypted amount
+/// or type actually matches what the solvency proof committed to, rather than some unrelated
+/// value the prover chose to encrypt instead. A standard Chaum-Pedersen AND-composition: one
+/// leg ties the response to the Pedersen commitment, the other two tie it to the ciphertext.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TracingEqualityProof {
+  t_c: CompressedRistretto,
+  t_e1: CompressedRistretto,
+  t_e2: CompressedRistretto,
+  s_a: Scalar,
+  s_rp: Scalar,
+  s_re: Scalar,
+}
+
+/// Proves that `commitment = value*G + commitment_blinding*H` and `ciphertext =
+/// (r*G, value*G + r*public_key)` hide the same `value`.
+fn prove_tracing_equality<R: CryptoRng + RngCore>(prng: &mut R,
+                                                  public_key: &CompressedRistretto,
+                                                  value: Scalar,
+                                                  commitment_blinding: Scalar,
+                                                  r: Scalar,
+                                                  commitment: &CompressedRistretto,
+                                                  ciphertext: &ElGamalCiphertext)
+                                                  -> TracingEqualityProof {
+  let pc_gens = PedersenGens::default();
+  let g = pc_gens.B;
+  let h = pc_gens.B_blinding;
+  let pk = public_key.decompress().expect("valid tracer public key");
+
+  let k_a = Scalar::random(prng);
+  let k_rp = Scalar::random(prng);
+  let k_re = Scalar::random(prng);
+
+  let t_c = (k_a * g + k_rp * h).compress();
+  let t_e1 = (k_re * g).compress();
+  let t_e2 = (k_a * g + k_re * pk).compress();
+
+  let c = tracing_challenge(&[commitment, &ciphertext.0, &ciphertext.1, &t_c, &t_e1, &t_e2]);
+
+  TracingEqualityProof { t_c,
+                        t_e1,
+                        t_e2,
+                        s_a: k_a + c * value,
+                        s_rp: k_rp + c * commitment_blinding,
+                        s_re: k_re + c * r }
+}
+
+/// Verifies a [`TracingEqualityProof`] against the commitment and ciphertext it was produced
+/// for.
+fn verify_tracing_equality(proof: &TracingEqualityProof,
+                          public_key: &CompressedRistretto,
+                          commitment: &CompressedRistretto,
+                          ciphertext: &ElGamalCiphertext)
+                          -> bool {
+  let pc_gens = PedersenGens::default();
+  let g = pc_gens.B;
+  let h = pc_gens.B_blinding;
+  let pk = match public_key.decompress() {
+    Some(p) => p,
+    None => return false,
+  };
+  let commitment_point = match commitment.decompress() {
+    Some(p) => p,
+    None => return false,
+  };
+  let (e1, e2) = match (ciphertext.0.decompress(), ciphertext.1.decompress()) {
+    (Some(e1), Some(e2)) => (e1, e2),
+    _ => return false,
+  };
+  let (t_c, t_e1, t_e2) =
+    match (proof.t_c.decompress(), proof.t_e1.decompress(), proof.t_e2.decompress()) {
+      (Some(a), Some(b), Some(c)) => (a, b, c),
+      _ => return false,
+    };
+
+  let c = tracing_challenge(&[commitment, &ciphertext.0, &ciphertext.1,
+                              &proof.t_c, &proof.t_e1, &proof.t_e2]);
+
+  let lhs_c = proof.s_a * g + proof.s_rp * h;
+  let rhs_c = t_c + c * commitment_point;
+  let lhs_e1 = proof.s_re * g;
+  let rhs_e1 = t_e1 + c * e1;
+  let lhs_e2 = proof.s_a * g + proof.s_re * pk;
+  let rhs_e2 = t_e2 + c * e2;
+
+  lhs_c == rhs_c && lhs_e1 == rhs_e1 && lhs_e2 == rhs_e2
+}
+
+/// An ElGamal ciphertext of a hidden commitment's amount and asset-type scalar, each paired
+/// with a [`TracingEqualityProof`] against the commitment it was encrypted from. Stored
+/// alongside a commitment whenever the [`SolvencyAudit`] that proved it has a
+/// `tracer_public_key` set; `None` otherwise.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CommitmentTracingInfo {
+  pub amount_ciphertext: ElGamalCiphertext,
+  pub amount_proof: TracingEqualityProof,
+  pub type_ciphertext: ElGamalCiphertext,
+  pub type_proof: TracingEqualityProof,
+}
+
+/// Recovers `scalar` as a `u64`, provided it actually fits in `bits` (<= 64) -- i.e. it really
+/// was produced from a bounded integer, not some value that wrapped the scalar field. `amount`
+/// and `rate` fields are `pub`, so nothing stops a caller from pushing an out-of-range `Scalar`
+/// directly into `public_assets`/`hidden_assets`/`conversion_rates` without going through
+/// `add_hidden_asset` et al.; this is the check that catches that before it reaches the prover.
+fn scalar_to_bounded_u64(scalar: &Scalar, bits: u32) -> Option<u64> {
+  let bytes = scalar.to_bytes();
+  if bytes[8..].iter().any(|b| *b != 0) {
+    return None;
+  }
+  let value = u64::from_le_bytes(bytes[..8].try_into().ok()?);
+  if bits < 64 && value >> bits != 0 {
+    return None;
+  }
+  Some(value)
+}
+
+/// Computes `Σ amount_i · rate_i` for `entries` in `u128`, using `checked_mul`/`checked_add`
+/// throughout and rejecting (with `PlatformError::InputsError`, the closest existing variant --
+/// see the note on `prove_solvency_and_store` about why no dedicated variant exists here) if any
+/// per-term product or the running sum would exceed `2^64`. Every entry's amount must fit in
+/// `range_bits` bits regardless of whether its code has a conversion rate (this is also what
+/// `prove_range` later proves in zero-knowledge); an entry whose code has *no* conversion rate
+/// set only skips the weighted-sum term, since `prove_solvency` itself already fails on
+/// incomplete rate tables (`ZeiError::SolvencyProveError`) and this pass isn't meant to pre-empt
+/// that, only to guard the arithmetic of entries it can actually weigh.
+fn checked_weighted_total(entries: &[AssetAmountAndCode],
+                          rates: &LinearMap<Scalar, Scalar>,
+                          range_bits: u32)
+                          -> Result<u128, PlatformError> {
+  let bound = 1u128 << 64;
+  let mut total: u128 = 0;
+  for (amount, code) in entries {
+    let amount = scalar_to_bounded_u64(amount, range_bits).ok_or_else(|| {
+                                                             PlatformError::InputsError(error_location!())
+                                                           })?;
+    let rate = match rates.get(code) {
+      Some(rate) => rate,
+      None => continue,
+    };
+    let rate = scalar_to_bounded_u64(rate, 64).ok_or_else(|| {
+                                                 PlatformError::InputsError(error_location!())
+                                               })?;
+    let term = (amount as u128).checked_mul(rate as u128)
+                               .ok_or_else(|| PlatformError::InputsError(error_location!()))?;
+    if term >= bound {
+      return Err(PlatformError::InputsError(error_location!()));
+    }
+    total = total.checked_add(term)
+                 .ok_or_else(|| PlatformError::InputsError(error_location!()))?;
+    if total >= bound {
+      return Err(PlatformError::InputsError(error_location!()));
+    }
+  }
+  Ok(total)
+}
+
+/// Proves `value` (recovered from `commitment`'s blinding `blinding`) lies in `[0, 2^range_bits)`,
+/// binding the proof to the exact commitment `prove_solvency_and_store` already computed for it.
+fn prove_range(value: u64, blinding: Scalar, range_bits: u32) -> Vec<u8> {
+  let pc_gens = PedersenGens::default();
+  let bp_gens = BulletproofGens::new(range_bits as usize, 1);
+  let mut transcript = Transcript::new(b"SolvencyHiddenAmountRange");
+  let (proof, _commitment) = RangeProof::prove_single(&bp_gens,
+                                                       &pc_gens,
+                                                       &mut transcript,
+                                                       value,
+                                                       &blinding,
+                                                       range_bits as usize).expect("range proof generation");
+  proof.to_bytes()
+}
+
+/// Verifies a [`prove_range`] proof against the amount component of a stored commitment.
+fn verify_range(proof_bytes: &[u8], commitment: &CompressedRistretto, range_bits: u32) -> bool {
+  let proof = match RangeProof::from_bytes(proof_bytes) {
+    Ok(proof) => proof,
+    Err(_) => return false,
+  };
+  let pc_gens = PedersenGens::default();
+  let bp_gens = BulletproofGens::new(range_bits as usize, 1);
+  let mut transcript = Transcript::new(b"SolvencyHiddenAmountRange");
+  proof.verify_single(&bp_gens, &pc_gens, &mut transcript, commitment, range_bits as usize)
+       .is_ok()
+}
+
+/// Extends `cache` with a fresh `(commitment, blinding)` pair for every entry of `entries` past
+/// what's already cached, first dropping any cached prefix that no longer matches `entries` at
+/// that index -- the same "`pub` fields let a caller bypass the `add_*` methods" concern
+/// documented on `scalar_to_bounded_u64`, here applied so a stale or tampered cache entry can't
+/// silently reappear in a fresh proof. Returns the full blinding list and commitment list to
+/// prove over, one pair per entry, with cached ones reused as-is.
+fn sync_hidden_cache<R: CryptoRng + RngCore>(
+  cache: &mut Vec<((CompressedRistretto, CompressedRistretto), (Scalar, Scalar))>,
+  entries: &[AssetAmountAndCode],
+  prng: &mut R)
+  -> (Vec<(Scalar, Scalar)>, Vec<(CompressedRistretto, CompressedRistretto)>) {
+  let pc_gens = PedersenGens::default();
+  cache.truncate(entries.len());
+  let stale_from = cache.iter().zip(entries.iter()).position(|((commitment, (ba, bt)), (a, t))| {
+                                 pc_gens.commit(*a, *ba).compress() != commitment.0
+                                 || pc_gens.commit(*t, *bt).compress() != commitment.1
+                               });
+  if let Some(stale_from) = stale_from {
+    cache.truncate(stale_from);
+  }
+  for (a, t) in entries.iter().skip(cache.len()) {
+    let blinding = (Scalar::random(prng), Scalar::random(prng));
+    let commitment =
+      (pc_gens.commit(*a, blinding.0).compress(), pc_gens.commit(*t, blinding.1).compress());
+    cache.push((commitment, blinding));
+  }
+  (cache.iter().map(|(_, blinding)| *blinding).collect(),
+   cache.iter().map(|(commitment, _)| *commitment).collect())
+}
+
 /// Asset and liability information, and associated solvency proof if exists
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct AssetAndLiabilityAccount {
@@ -47,6 +330,38 @@ pub struct AssetAndLiabilityAccount {
   /// * Solvency hasn't been proved
   /// * Assets or liabilities have been updated
   pub proof: Option<Vec<u8>>,
+
+  /// Per-hidden-asset ElGamal openings for the audit's tracer, null iff any of the following:
+  /// * Solvency hasn't been proved
+  /// * Assets or liabilities have been updated
+  /// * The proving `SolvencyAudit` had no `tracer_public_key` set
+  pub hidden_assets_tracing: Option<Vec<CommitmentTracingInfo>>,
+
+  /// Per-hidden-liability ElGamal openings for the audit's tracer, null under the same
+  /// conditions as `hidden_assets_tracing`.
+  pub hidden_liabilities_tracing: Option<Vec<CommitmentTracingInfo>>,
+
+  /// Per-hidden-asset Bulletproof range proofs (serialized), each constraining the
+  /// corresponding `hidden_assets_commitments` entry's amount to `[0, 2^range_bits)` at the
+  /// `range_bits` in effect when the proof was made. Null under the same conditions as
+  /// `hidden_assets_commitments`.
+  pub hidden_assets_range_proofs: Option<Vec<Vec<u8>>>,
+
+  /// Per-hidden-liability Bulletproof range proofs, as `hidden_assets_range_proofs`.
+  pub hidden_liabilities_range_proofs: Option<Vec<Vec<u8>>>,
+
+  /// Cached `(commitment, blinding)` pairs mirroring a prefix of `hidden_assets`, refreshed by
+  /// every successful `prove_solvency_and_store*`/`reprove_incremental*` call.
+  /// `reprove_incremental` reuses these for entries that haven't changed instead of sampling a
+  /// fresh blinding for the whole vector every time. Not serialized: the blindings only matter
+  /// to the process that sampled them, and a freshly deserialized account simply has nothing
+  /// cached, i.e. its next incremental re-prove resamples everything.
+  #[serde(skip)]
+  hidden_assets_cache: Vec<(AssetCommitment, (Scalar, Scalar))>,
+
+  /// As `hidden_assets_cache`, for `hidden_liabilities`.
+  #[serde(skip)]
+  hidden_liabilities_cache: Vec<(LiabilityCommitment, (Scalar, Scalar))>,
 }
 
 impl AssetAndLiabilityAccount {
@@ -56,17 +371,92 @@ impl AssetAndLiabilityAccount {
     self.hidden_assets_commitments = None;
     self.hidden_liabilities_commitments = None;
     self.proof = None;
+    self.hidden_assets_tracing = None;
+    self.hidden_liabilities_tracing = None;
+    self.hidden_assets_range_proofs = None;
+    self.hidden_liabilities_range_proofs = None;
   }
 
-  /// Adds the commitments to hidden assets and liabilities, and the solvency proof.
-  /// Used when the the solvency is proved.
+  /// Adds the commitments to hidden assets and liabilities, the solvency proof, the per-hidden-
+  /// amount range proofs, and (if the proving audit had a tracer key set) the per-commitment
+  /// ElGamal openings for that tracer. Used when the the solvency is proved.
+  #[allow(clippy::too_many_arguments)]
   pub fn add_commitments_and_proof(&mut self,
                                    hidden_assets_commitments: Vec<AssetCommitment>,
                                    hidden_liabilities_commitments: Vec<LiabilityCommitment>,
-                                   proof: R1CSProof) {
+                                   proof: R1CSProof,
+                                   hidden_assets_tracing: Option<Vec<CommitmentTracingInfo>>,
+                                   hidden_liabilities_tracing: Option<Vec<CommitmentTracingInfo>>,
+                                   hidden_assets_range_proofs: Vec<Vec<u8>>,
+                                   hidden_liabilities_range_proofs: Vec<Vec<u8>>) {
     self.hidden_assets_commitments = Some(hidden_assets_commitments);
     self.hidden_liabilities_commitments = Some(hidden_liabilities_commitments);
     self.proof = Some(proof.to_bytes());
+    self.hidden_assets_tracing = hidden_assets_tracing;
+    self.hidden_liabilities_tracing = hidden_liabilities_tracing;
+    self.hidden_assets_range_proofs = Some(hidden_assets_range_proofs);
+    self.hidden_liabilities_range_proofs = Some(hidden_liabilities_range_proofs);
+  }
+
+  /// Decrypts this account's hidden-asset ElGamal openings with `secret_key`, returning each
+  /// commitment's `(amount, asset_code)` in hidden-asset order. `candidate_codes` is the list
+  /// of asset codes the tracer is prepared to recognize -- the type ciphertext decrypts to a
+  /// curve point, not a scalar, so (as with `trace_assets` in the wasm bindings) identifying
+  /// which code it is means matching it against known candidates rather than inverting an
+  /// arbitrary discrete log. Returns `PlatformError::InputsError` if tracing wasn't recorded
+  /// (no tracer key was set when the proof was made). A commitment is silently omitted if its
+  /// `TracingEqualityProof` doesn't verify, its amount exceeds `TRACING_DECRYPT_BOUND`, or its
+  /// type doesn't match any candidate -- each of those means the opening can't be trusted or
+  /// can't be identified, not that tracing itself failed.
+  pub fn trace_openings(&self,
+                        secret_key: Scalar,
+                        candidate_codes: &[AssetTypeCode])
+                        -> Result<Vec<(u64, AssetTypeCode)>, PlatformError> {
+    let tracing = self.hidden_assets_tracing
+                      .as_ref()
+                      .ok_or_else(|| PlatformError::InputsError(error_location!()))?;
+    let commitments = self.hidden_assets_commitments
+                          .as_ref()
+                          .ok_or_else(|| PlatformError::InputsError(error_location!()))?;
+    let public_key = tracing.first()
+                            .map(|_| secret_key * PedersenGens::default().B)
+                            .map(|p| p.compress());
+    let public_key = match public_key {
+      Some(p) => p,
+      None => return Ok(Vec::new()),
+    };
+    let candidate_scalars: Vec<(Scalar, AssetTypeCode)> =
+      candidate_codes.iter()
+                     .map(|code| (asset_type_to_scalar(&code.val), code.clone()))
+                     .collect();
+    let g = PedersenGens::default().B;
+    let openings =
+      tracing.iter()
+             .zip(commitments.iter())
+             .filter_map(|(info, (amount_commitment, type_commitment))| {
+               if !verify_tracing_equality(&info.amount_proof,
+                                           &public_key,
+                                           amount_commitment,
+                                           &info.amount_ciphertext)
+                  || !verify_tracing_equality(&info.type_proof,
+                                              &public_key,
+                                              type_commitment,
+                                              &info.type_ciphertext)
+               {
+                 return None;
+               }
+               let amount = decrypt_exponential(&info.amount_ciphertext, secret_key)?;
+               let e1 = info.type_ciphertext.0.decompress()?;
+               let e2 = info.type_ciphertext.1.decompress()?;
+               let type_point = e2 - secret_key * e1;
+               let code = candidate_scalars.iter()
+                                           .find(|(scalar, _)| *scalar * g == type_point)?
+                                           .1
+                                           .clone();
+               Some((amount, code))
+             })
+             .collect();
+    Ok(openings)
   }
 
   /// Adds a public asset and remove the solvency proof.
@@ -94,14 +484,56 @@ impl AssetAndLiabilityAccount {
   }
 }
 
+/// The bit-length `SolvencyAudit::default` constrains every hidden amount to, absent an
+/// explicit `set_range_bits` call: wide enough for any real `u64` amount, narrow enough that
+/// `checked_weighted_total`'s `2^64` overflow bound is meaningful.
+const DEFAULT_RANGE_BITS: u32 = 64;
+
 /// Used to audit the solvency.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SolvencyAudit {
   /// Table mapping each asset code to its conversion rate.
   pub conversion_rates: Vec<AssetCodeAndRate>,
+
+  /// The tracer who should be able to open individual hidden-asset/liability commitments,
+  /// if any. Null means `prove_solvency_and_store` produces no `CommitmentTracingInfo`.
+  pub tracer_public_key: Option<CompressedRistretto>,
+
+  /// The bit-length every hidden amount is range-proved and overflow-checked against, i.e.
+  /// each must fall in `[0, 2^range_bits)`. Defaults to `DEFAULT_RANGE_BITS`; tighten it with
+  /// `set_range_bits` for deployments that know amounts fit a narrower range.
+  pub range_bits: u32,
+}
+
+impl Default for SolvencyAudit {
+  fn default() -> Self {
+    SolvencyAudit { conversion_rates: Vec::new(),
+                    tracer_public_key: None,
+                    range_bits: DEFAULT_RANGE_BITS }
+  }
 }
 
 impl SolvencyAudit {
+  /// Sets (or replaces) the tracer public key. Takes effect on the next `prove_solvency_and_store*`
+  /// call; existing stored commitments' tracing openings are unaffected until re-proved.
+  pub fn set_tracer_public_key(&mut self, tracer_public_key: CompressedRistretto) {
+    self.tracer_public_key = Some(tracer_public_key);
+  }
+
+  /// Tightens (or widens) the bit-length hidden amounts are range-proved and overflow-checked
+  /// against. Takes effect on the next `prove_solvency_and_store*`/`verify_solvency*` call.
+  ///
+  /// `range_bits` must be one of `bulletproofs`' supported range-proof widths -- 8, 16, 32, or
+  /// 64 -- since `prove_range` hands it straight to `RangeProof::prove_single`, which panics
+  /// for any other bit-length instead of returning a `Result`.
+  pub fn set_range_bits(&mut self, range_bits: u32) -> Result<(), PlatformError> {
+    if !matches!(range_bits, 8 | 16 | 32 | 64) {
+      return Err(PlatformError::InputsError(error_location!()));
+    }
+    self.range_bits = range_bits;
+    Ok(())
+  }
+
   /// Sets conversion rate for the asset.
   pub fn set_rate(&mut self, code: AssetTypeCode, rate: u64) {
     self.conversion_rates
@@ -118,32 +550,52 @@ impl SolvencyAudit {
 
   /// Proves the solvency and stores the commitments and proof.
   /// Must be used before `verify_solvency`.
+  ///
+  /// Draws blinding factors from OS entropy. Use `prove_solvency_and_store_with_rng` to
+  /// supply a specific generator instead (e.g. for reproducible tests).
   pub fn prove_solvency_and_store(&self,
                                   account: &mut AssetAndLiabilityAccount)
                                   -> Result<(), PlatformError> {
-    // Prove the solvency
-    let mut prng = ChaChaRng::from_seed([0u8; 32]);
-    let hidden_assets_size = account.hidden_assets.len();
-    let hidden_liabilities_size = account.hidden_liabilities.len();
-    let assets_hiddens =
-      vec![(Scalar::random(&mut prng), Scalar::random(&mut prng)); hidden_assets_size];
-    let liabilities_hiddens =
-      vec![(Scalar::random(&mut prng), Scalar::random(&mut prng)); hidden_liabilities_size];
+    let mut prng = ChaChaRng::from_entropy();
+    self.prove_solvency_and_store_with_rng(account, &mut prng)
+  }
+
+  /// As `prove_solvency_and_store`, but draws the hidden assets'/liabilities' Pedersen
+  /// blinding factors from the caller-supplied `prng` rather than OS entropy. `prng` must
+  /// be a real CSPRNG: reusing a fixed seed (or any other non-random source) makes the
+  /// commitments it produces fail to hide the underlying amounts, since two accounts with
+  /// the same figures and the same blindings commit to identical values.
+  pub fn prove_solvency_and_store_with_rng<R: CryptoRng + RngCore>(
+    &self,
+    account: &mut AssetAndLiabilityAccount,
+    prng: &mut R)
+    -> Result<(), PlatformError> {
     let mut rates = LinearMap::new();
     for (code, rate) in self.conversion_rates.clone() {
       rates.insert(code, rate);
     }
-    let proof =
-      prove_solvency(&account.hidden_assets,
-                     &assets_hiddens,
-                     &account.public_assets,
-                     &account.hidden_liabilities,
-                     &liabilities_hiddens,
-                     &account.public_liabilities,
-                     &rates).or_else(|e| Err(PlatformError::ZeiError(error_location!(), e)))?;
 
-    // Commit the hidden assets and liabilities
+    // `Σ amount_i · rate_i` is computed by `prove_solvency`/`verify_solvency` over curve
+    // scalars, which wrap mod the (astronomically large) group order rather than `2^64` -- but
+    // `public_assets`/`hidden_assets`/`public_liabilities`/`hidden_liabilities` are all `pub`
+    // fields, so nothing stops a caller from pushing an amount or rate `Scalar` that was never a
+    // bounded `u64` to begin with. Reject that here, in `u128`, before it ever reaches the
+    // prover, rather than letting an out-of-range figure "prove" an insolvent account solvent.
+    checked_weighted_total(&account.public_assets, &rates, self.range_bits)?;
+    checked_weighted_total(&account.hidden_assets, &rates, self.range_bits)?;
+    checked_weighted_total(&account.public_liabilities, &rates, self.range_bits)?;
+    checked_weighted_total(&account.hidden_liabilities, &rates, self.range_bits)?;
+
+    // Sample fresh blindings and commitments for every hidden entry -- a full prove never
+    // reuses the incremental-reproving cache, but `finish_proving` refreshes it below so a
+    // later `reprove_incremental` call has a clean cache to build on.
     let pc_gens = PedersenGens::default();
+    let assets_hiddens: Vec<_> =
+      (0..account.hidden_assets.len()).map(|_| (Scalar::random(prng), Scalar::random(prng)))
+                                      .collect();
+    let liabilities_hiddens: Vec<_> =
+      (0..account.hidden_liabilities.len()).map(|_| (Scalar::random(prng), Scalar::random(prng)))
+                                           .collect();
     let hidden_assets_commitments: Vec<AssetCommitment> =
       account.hidden_assets
              .iter()
@@ -161,16 +613,229 @@ impl SolvencyAudit {
              })
              .collect();
 
-    // Update data
+    self.finish_proving(account,
+                        prng,
+                        &rates,
+                        assets_hiddens,
+                        liabilities_hiddens,
+                        hidden_assets_commitments,
+                        hidden_liabilities_commitments)
+  }
+
+  /// Re-proves solvency after a mutation, reusing the cached commitment and blinding for every
+  /// hidden entry that hasn't changed since the last successful `prove_solvency_and_store*`/
+  /// `reprove_incremental*` call instead of resampling the whole vector. The vendored `zei` has
+  /// no incremental proving entry point, so the R1CS proof itself is still rebuilt over the
+  /// full (cached + new) commitment set every time -- this only saves the blinding-sampling and
+  /// commitment work for entries that didn't change, not the proof generation itself.
+  ///
+  /// Draws fresh blinding factors (for new/changed entries only) from OS entropy. Use
+  /// `reprove_incremental_with_rng` to supply a specific generator instead.
+  pub fn reprove_incremental(&self,
+                             account: &mut AssetAndLiabilityAccount)
+                             -> Result<(), PlatformError> {
+    let mut prng = ChaChaRng::from_entropy();
+    self.reprove_incremental_with_rng(account, &mut prng)
+  }
+
+  /// As `reprove_incremental`, but draws any newly-needed blinding factors from the
+  /// caller-supplied `prng` rather than OS entropy -- see the same caveat on
+  /// `prove_solvency_and_store_with_rng`.
+  pub fn reprove_incremental_with_rng<R: CryptoRng + RngCore>(
+    &self,
+    account: &mut AssetAndLiabilityAccount,
+    prng: &mut R)
+    -> Result<(), PlatformError> {
+    let mut rates = LinearMap::new();
+    for (code, rate) in self.conversion_rates.clone() {
+      rates.insert(code, rate);
+    }
+
+    checked_weighted_total(&account.public_assets, &rates, self.range_bits)?;
+    checked_weighted_total(&account.hidden_assets, &rates, self.range_bits)?;
+    checked_weighted_total(&account.public_liabilities, &rates, self.range_bits)?;
+    checked_weighted_total(&account.hidden_liabilities, &rates, self.range_bits)?;
+
+    let (assets_hiddens, hidden_assets_commitments) =
+      sync_hidden_cache(&mut account.hidden_assets_cache, &account.hidden_assets, prng);
+    let (liabilities_hiddens, hidden_liabilities_commitments) =
+      sync_hidden_cache(&mut account.hidden_liabilities_cache, &account.hidden_liabilities, prng);
+
+    self.finish_proving(account,
+                        prng,
+                        &rates,
+                        assets_hiddens,
+                        liabilities_hiddens,
+                        hidden_assets_commitments,
+                        hidden_liabilities_commitments)
+  }
+
+  /// Finishes a solvency proof given already-decided hidden blindings and commitments -- shared
+  /// by `prove_solvency_and_store_with_rng` (every blinding sampled fresh) and
+  /// `reprove_incremental_with_rng` (cached blindings reused for unchanged entries): proves the
+  /// R1CS relation, builds tracer openings and range proofs, stores everything on `account`, and
+  /// refreshes `account`'s incremental-reproving cache to match what was just proved.
+  fn finish_proving<R: CryptoRng + RngCore>(&self,
+                                            account: &mut AssetAndLiabilityAccount,
+                                            prng: &mut R,
+                                            rates: &LinearMap<Scalar, Scalar>,
+                                            assets_hiddens: Vec<(Scalar, Scalar)>,
+                                            liabilities_hiddens: Vec<(Scalar, Scalar)>,
+                                            hidden_assets_commitments: Vec<AssetCommitment>,
+                                            hidden_liabilities_commitments: Vec<LiabilityCommitment>)
+                                            -> Result<(), PlatformError> {
+    let proof =
+      prove_solvency(&account.hidden_assets,
+                     &assets_hiddens,
+                     &account.public_assets,
+                     &account.hidden_liabilities,
+                     &liabilities_hiddens,
+                     &account.public_liabilities,
+                     rates).or_else(|e| Err(PlatformError::ZeiError(error_location!(), e)))?;
+
+    // If a tracer is configured, encrypt each hidden commitment's opening for them and prove
+    // the ciphertexts match the commitments just computed above.
+    let hidden_assets_tracing = self.tracer_public_key.map(|tracer_public_key| {
+                                  Self::trace_commitments(prng,
+                                                          &tracer_public_key,
+                                                          &account.hidden_assets,
+                                                          &assets_hiddens,
+                                                          &hidden_assets_commitments)
+                                });
+    let hidden_liabilities_tracing = self.tracer_public_key.map(|tracer_public_key| {
+                                       Self::trace_commitments(prng,
+                                                               &tracer_public_key,
+                                                               &account.hidden_liabilities,
+                                                               &liabilities_hiddens,
+                                                               &hidden_liabilities_commitments)
+                                     });
+
+    // Bind an explicit Bulletproof range proof to each hidden amount's commitment, so the
+    // *verifier* -- not just this validation pass over cleartext figures -- is protected
+    // against an amount Scalar that wraps outside `[0, 2^range_bits)`.
+    let hidden_assets_range_proofs: Vec<Vec<u8>> =
+      account.hidden_assets
+             .iter()
+             .zip(assets_hiddens.iter())
+             .map(|((a, _), (ba, _))| {
+               let value = scalar_to_bounded_u64(a, self.range_bits).expect(
+                 "checked_weighted_total already validated every hidden amount fits range_bits",
+               );
+               prove_range(value, *ba, self.range_bits)
+             })
+             .collect();
+    let hidden_liabilities_range_proofs: Vec<Vec<u8>> =
+      account.hidden_liabilities
+             .iter()
+             .zip(liabilities_hiddens.iter())
+             .map(|((a, _), (ba, _))| {
+               let value = scalar_to_bounded_u64(a, self.range_bits).expect(
+                 "checked_weighted_total already validated every hidden amount fits range_bits",
+               );
+               prove_range(value, *ba, self.range_bits)
+             })
+             .collect();
+
+    // Refresh the incremental-reproving cache to mirror exactly what was just proved, before
+    // `hidden_assets_commitments`/`hidden_liabilities_commitments` are moved into `account`.
+    account.hidden_assets_cache = hidden_assets_commitments.iter()
+                                                            .copied()
+                                                            .zip(assets_hiddens.iter().copied())
+                                                            .collect();
+    account.hidden_liabilities_cache = hidden_liabilities_commitments.iter()
+                                                                      .copied()
+                                                                      .zip(liabilities_hiddens.iter()
+                                                                                              .copied())
+                                                                      .collect();
+
     account.add_commitments_and_proof(hidden_assets_commitments,
                                       hidden_liabilities_commitments,
-                                      proof);
+                                      proof,
+                                      hidden_assets_tracing,
+                                      hidden_liabilities_tracing,
+                                      hidden_assets_range_proofs,
+                                      hidden_liabilities_range_proofs);
     Ok(())
   }
 
+  /// Builds a [`CommitmentTracingInfo`] for every `(amount, code)`/blinding/commitment triple,
+  /// encrypting each under `tracer_public_key` and proving the ciphertext matches the
+  /// commitment it was derived from.
+  fn trace_commitments<R: CryptoRng + RngCore>(prng: &mut R,
+                                               tracer_public_key: &CompressedRistretto,
+                                               amounts_and_codes: &[AssetAmountAndCode],
+                                               hiddens: &[(Scalar, Scalar)],
+                                               commitments: &[(CompressedRistretto,
+                                                 CompressedRistretto)])
+                                               -> Vec<CommitmentTracingInfo> {
+    amounts_and_codes.iter()
+                     .zip(hiddens.iter())
+                     .zip(commitments.iter())
+                     .map(|(((amount, code), (ba, bt)), (amount_commitment, type_commitment))| {
+                       let r_amount = Scalar::random(prng);
+                       let r_type = Scalar::random(prng);
+                       let amount_ciphertext =
+                         elgamal_encrypt(tracer_public_key, *amount, r_amount);
+                       let type_ciphertext = elgamal_encrypt(tracer_public_key, *code, r_type);
+                       let amount_proof = prove_tracing_equality(prng,
+                                                                 tracer_public_key,
+                                                                 *amount,
+                                                                 *ba,
+                                                                 r_amount,
+                                                                 amount_commitment,
+                                                                 &amount_ciphertext);
+                       let type_proof = prove_tracing_equality(prng,
+                                                               tracer_public_key,
+                                                               *code,
+                                                               *bt,
+                                                               r_type,
+                                                               type_commitment,
+                                                               &type_ciphertext);
+                       CommitmentTracingInfo { amount_ciphertext,
+                                               amount_proof,
+                                               type_ciphertext,
+                                               type_proof }
+                     })
+                     .collect()
+  }
+
   /// Verifies the solvency proof.
   /// Must not be used before `prove_solvency_and_store`.
   pub fn verify_solvency(&self, account: &AssetAndLiabilityAccount) -> Result<(), PlatformError> {
+    let rates = self.rates_map();
+    Self::verify_solvency_with_rates(account, &rates, self.range_bits)
+  }
+
+  /// Verifies many accounts' solvency proofs against this audit's shared rate table.
+  ///
+  /// Building the rate `LinearMap` is the one piece of per-call work `verify_solvency`
+  /// redundantly repeats for every account; this builds it exactly once and reuses it
+  /// across the whole batch. The underlying `zei` version vendored here doesn't expose a
+  /// batched Bulletproof verifier (no combined multi-scalar-multiplication entry point), so
+  /// each proof is still checked independently -- but a bad proof only fails its own slot,
+  /// rather than aborting accounts that sort after it, which is the other property a real
+  /// exchange auditing thousands of sub-accounts needs.
+  pub fn verify_solvency_batch(&self,
+                               accounts: &[&AssetAndLiabilityAccount])
+                               -> Vec<Result<(), PlatformError>> {
+    let rates = self.rates_map();
+    accounts.iter()
+            .map(|account| Self::verify_solvency_with_rates(account, &rates, self.range_bits))
+            .collect()
+  }
+
+  fn rates_map(&self) -> LinearMap<Scalar, Scalar> {
+    let mut rates = LinearMap::new();
+    for (code, rate) in self.conversion_rates.clone() {
+      rates.insert(code, rate);
+    }
+    rates
+  }
+
+  fn verify_solvency_with_rates(account: &AssetAndLiabilityAccount,
+                                rates: &LinearMap<Scalar, Scalar>,
+                                range_bits: u32)
+                                -> Result<(), PlatformError> {
     let hidden_assets_commitments = if let Some(commitments) = &account.hidden_assets_commitments {
       commitments
     } else {
@@ -190,17 +855,49 @@ impl SolvencyAudit {
       println!("Prove the solvency first.");
       return Err(PlatformError::InputsError(error_location!()));
     };
-    let mut rates = LinearMap::new();
-    for (code, rate) in self.conversion_rates.clone() {
-      rates.insert(code, rate);
-    }
+
+    // Check the range proofs binding every hidden amount to [0, 2^range_bits) before trusting
+    // the R1CS solvency proof over the same commitments.
+    Self::verify_range_proofs(&account.hidden_assets_range_proofs,
+                              hidden_assets_commitments,
+                              range_bits)?;
+    Self::verify_range_proofs(&account.hidden_liabilities_range_proofs,
+                              hidden_liabilities_commitments,
+                              range_bits)?;
+
     verify_solvency(hidden_assets_commitments,
                     &account.public_assets,
                     hidden_liabilities_commitments,
                     &account.public_liabilities,
-                    &rates,
+                    rates,
                     &proof).or_else(|e| Err(PlatformError::ZeiError(error_location!(), e)))
   }
+
+  /// Checks that `range_proofs` (one per `commitments` entry) each verify against that entry's
+  /// amount component. `commitments` is generic over `AssetCommitment`/`LiabilityCommitment`,
+  /// which are structurally the same tuple type.
+  fn verify_range_proofs(range_proofs: &Option<Vec<Vec<u8>>>,
+                         commitments: &[(CompressedRistretto, CompressedRistretto)],
+                         range_bits: u32)
+                         -> Result<(), PlatformError> {
+    let range_proofs = match range_proofs {
+      Some(proofs) => proofs,
+      None => {
+        println!("Missing range proofs for the hidden amounts. Prove the solvency first.");
+        return Err(PlatformError::InputsError(error_location!()));
+      }
+    };
+    if range_proofs.len() != commitments.len() {
+      return Err(PlatformError::InputsError(error_location!()));
+    }
+    for (proof_bytes, (amount_commitment, _)) in range_proofs.iter().zip(commitments.iter()) {
+      if !verify_range(proof_bytes, amount_commitment, range_bits) {
+        println!("A hidden amount's range proof failed to verify.");
+        return Err(PlatformError::InputsError(error_location!()));
+      }
+    }
+    Ok(())
+  }
 }
 
 #[cfg(test)]
@@ -434,4 +1131,241 @@ mod tests {
       }
     }
   }
+
+  #[test]
+  fn test_prove_solvency_with_rng_is_hiding() {
+    // Start a solvency audit process and set the asset conversion rates
+    let mut audit = SolvencyAudit::default();
+    let codes = add_conversion_rate_complete(&mut audit);
+
+    // Two accounts with identical hidden assets and liabilities
+    let mut account_a = &mut AssetAndLiabilityAccount::default();
+    add_hidden_assets(&mut account_a, codes);
+    add_hidden_liabilities_smaller(&mut account_a, codes);
+    let mut account_b = &mut AssetAndLiabilityAccount::default();
+    add_hidden_assets(&mut account_b, codes);
+    add_hidden_liabilities_smaller(&mut account_b, codes);
+
+    // Proving with two differently-seeded RNGs (standing in for OS entropy) must not yield
+    // the same commitments for the same underlying figures
+    let mut rng_a = ChaChaRng::from_seed([1u8; 32]);
+    let mut rng_b = ChaChaRng::from_seed([2u8; 32]);
+    audit.prove_solvency_and_store_with_rng(&mut account_a, &mut rng_a)
+         .unwrap();
+    audit.prove_solvency_and_store_with_rng(&mut account_b, &mut rng_b)
+         .unwrap();
+
+    assert_ne!(account_a.hidden_assets_commitments, account_b.hidden_assets_commitments);
+    assert_ne!(account_a.hidden_liabilities_commitments,
+               account_b.hidden_liabilities_commitments);
+
+    // Both proofs still verify
+    audit.verify_solvency(&account_a).unwrap();
+    audit.verify_solvency(&account_b).unwrap();
+  }
+
+  #[test]
+  fn test_verify_solvency_batch_mixed() {
+    // Start a solvency audit process and set the asset conversion rates
+    let mut audit = SolvencyAudit::default();
+    let codes = add_conversion_rate_complete(&mut audit);
+
+    // One account that proves solvent
+    let mut solvent_account = &mut AssetAndLiabilityAccount::default();
+    add_hidden_assets(&mut solvent_account, codes);
+    add_hidden_liabilities_smaller(&mut solvent_account, codes);
+    audit.prove_solvency_and_store(&mut solvent_account).unwrap();
+
+    // One account that proves insolvent
+    let mut insolvent_account = &mut AssetAndLiabilityAccount::default();
+    add_hidden_assets(&mut insolvent_account, codes);
+    add_hidden_liabilities_larger(&mut insolvent_account, codes);
+    audit.prove_solvency_and_store(&mut insolvent_account).unwrap();
+
+    // Batch-verifying both should report each account's own result, not abort on the first
+    // failure
+    let results = audit.verify_solvency_batch(&[&solvent_account, &insolvent_account]);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    match &results[1] {
+      Err(PlatformError::ZeiError(_, ZeiError::SolvencyVerificationError)) => {}
+      unexpected_result => {
+        panic!(format!("Expected ZeiError::SolvencyVerificationError, found {:?}.",
+                       unexpected_result));
+      }
+    }
+  }
+
+  #[test]
+  fn test_elgamal_encrypt_decrypt_roundtrip() {
+    let mut prng = ChaChaRng::from_seed([7u8; 32]);
+    let (sk, pk) = elgamal_keygen(&mut prng);
+    let value = Scalar::from(42u64);
+    let r = Scalar::random(&mut prng);
+    let ciphertext = elgamal_encrypt(&pk, value, r);
+    assert_eq!(decrypt_exponential(&ciphertext, sk), Some(42));
+  }
+
+  #[test]
+  fn test_tracing_equality_proof_rejects_mismatched_commitment() {
+    let mut prng = ChaChaRng::from_seed([8u8; 32]);
+    let (sk, pk) = elgamal_keygen(&mut prng);
+    let pc_gens = PedersenGens::default();
+
+    let value = Scalar::from(99u64);
+    let blinding = Scalar::random(&mut prng);
+    let r = Scalar::random(&mut prng);
+    let commitment = pc_gens.commit(value, blinding).compress();
+    let ciphertext = elgamal_encrypt(&pk, value, r);
+
+    let proof =
+      prove_tracing_equality(&mut prng, &pk, value, blinding, r, &commitment, &ciphertext);
+    assert!(verify_tracing_equality(&proof, &pk, &commitment, &ciphertext));
+
+    // A proof for one commitment must not verify against an unrelated one
+    let other_commitment = pc_gens.commit(Scalar::from(100u64), blinding).compress();
+    assert!(!verify_tracing_equality(&proof, &pk, &other_commitment, &ciphertext));
+    let _ = sk;
+  }
+
+  #[test]
+  fn test_prove_solvency_and_store_with_tracer_traces_openings() {
+    // Start a solvency audit process and register a tracer
+    let mut audit = SolvencyAudit::default();
+    let mut prng = ChaChaRng::from_seed([9u8; 32]);
+    let (tracer_sk, tracer_pk) = elgamal_keygen(&mut prng);
+    audit.set_tracer_public_key(tracer_pk);
+
+    // Set conversion rates for three real asset codes we can later pass as trace candidates
+    let asset_codes = [AssetTypeCode::gen_random(),
+                       AssetTypeCode::gen_random(),
+                       AssetTypeCode::gen_random()];
+    for code in &asset_codes {
+      audit.set_rate(*code, 1);
+    }
+    let scalar_codes = (asset_type_to_scalar(&asset_codes[0].val),
+                        asset_type_to_scalar(&asset_codes[1].val),
+                        asset_type_to_scalar(&asset_codes[2].val));
+
+    // Create an account and add hidden assets and liabilities
+    let mut account = &mut AssetAndLiabilityAccount::default();
+    add_hidden_assets(&mut account, scalar_codes);
+    add_hidden_liabilities_smaller(&mut account, scalar_codes);
+
+    // Prove the solvency; tracing info should be attached for every hidden commitment
+    audit.prove_solvency_and_store_with_rng(&mut account, &mut prng).unwrap();
+    assert_eq!(account.hidden_assets_tracing.as_ref().unwrap().len(), 3);
+    assert_eq!(account.hidden_liabilities_tracing.as_ref().unwrap().len(), 3);
+
+    // The tracer recovers the exact amounts and codes that were hidden (add_hidden_assets
+    // hides 10/20/30 of asset_codes[0]/[1]/[2] respectively)
+    let mut openings = account.trace_openings(tracer_sk, &asset_codes).unwrap();
+    openings.sort_by_key(|(amount, _)| *amount);
+    assert_eq!(openings.len(), 3);
+    assert_eq!(openings[0], (10, asset_codes[0]));
+    assert_eq!(openings[1], (20, asset_codes[1]));
+    assert_eq!(openings[2], (30, asset_codes[2]));
+
+    // A wrong secret key shouldn't produce any trusted openings
+    let (wrong_sk, _) = elgamal_keygen(&mut prng);
+    assert!(account.trace_openings(wrong_sk, &asset_codes).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_prove_solvency_rejects_out_of_range_hidden_amount() {
+    // Start a solvency audit process and set the asset conversion rates
+    let mut audit = SolvencyAudit::default();
+    let codes = add_conversion_rate_complete(&mut audit);
+
+    // Bypass `add_hidden_asset` and push an amount Scalar that was never a bounded u64 to
+    // begin with -- `hidden_assets` is a pub field, so nothing else stops this
+    let mut account = &mut AssetAndLiabilityAccount::default();
+    let huge_amount = Scalar::from_bytes_mod_order([0xffu8; 32]);
+    account.hidden_assets.push((huge_amount, codes.0));
+
+    // Should fail before ever reaching the prover
+    match audit.prove_solvency_and_store(&mut account) {
+      Err(PlatformError::InputsError(_)) => {}
+      unexpected_result => {
+        panic!(format!("Expected InputsError, found {:?}.", unexpected_result));
+      }
+    }
+  }
+
+  #[test]
+  fn test_set_range_bits_rejects_amount_above_narrowed_bound() {
+    // Start a solvency audit process, set the asset conversion rates, and narrow range_bits
+    // to 8 bits (amounts must fall in [0, 256))
+    let mut audit = SolvencyAudit::default();
+    let codes = add_conversion_rate_complete(&mut audit);
+    audit.set_range_bits(8).unwrap();
+
+    // 2000 doesn't fit in 8 bits
+    let mut account = &mut AssetAndLiabilityAccount::default();
+    account.add_hidden_asset(2000, codes.0);
+
+    match audit.prove_solvency_and_store(&mut account) {
+      Err(PlatformError::InputsError(_)) => {}
+      unexpected_result => {
+        panic!(format!("Expected InputsError, found {:?}.", unexpected_result));
+      }
+    }
+
+    // An amount that does fit still proves and verifies normally
+    let mut account = &mut AssetAndLiabilityAccount::default();
+    account.add_hidden_asset(200, codes.0);
+    audit.prove_solvency_and_store(&mut account).unwrap();
+    assert_eq!(account.hidden_assets_range_proofs.as_ref().unwrap().len(), 1);
+    audit.verify_solvency(&account).unwrap();
+  }
+
+  #[test]
+  fn test_set_range_bits_rejects_unsupported_bit_length() {
+    // `bulletproofs` only supports 8/16/32/64-bit range proofs; anything else must be
+    // rejected here rather than panicking later inside `prove_range`.
+    let mut audit = SolvencyAudit::default();
+    match audit.set_range_bits(48) {
+      Err(PlatformError::InputsError(_)) => {}
+      unexpected_result => {
+        panic!(format!("Expected InputsError, found {:?}.", unexpected_result));
+      }
+    }
+    assert_eq!(audit.range_bits, DEFAULT_RANGE_BITS);
+  }
+
+  #[test]
+  fn test_reprove_incremental_reuses_cached_commitments() {
+    // Start a solvency audit process and set the asset conversion rates
+    let mut audit = SolvencyAudit::default();
+    let codes = add_conversion_rate_complete(&mut audit);
+
+    // Prove solvency over the initial hidden assets and liabilities
+    let mut account = &mut AssetAndLiabilityAccount::default();
+    add_hidden_assets(&mut account, codes);
+    add_hidden_liabilities_smaller(&mut account, codes);
+    let mut rng_a = ChaChaRng::from_seed([11u8; 32]);
+    audit.prove_solvency_and_store_with_rng(&mut account, &mut rng_a)
+         .unwrap();
+    let original_asset_commitments = account.hidden_assets_commitments.clone().unwrap();
+    let original_liability_commitments = account.hidden_liabilities_commitments.clone().unwrap();
+
+    // Add one more hidden asset, then incrementally re-prove with a differently-seeded rng
+    account.add_hidden_asset(40, codes.0);
+    assert!(account.hidden_assets_commitments.is_none());
+    let mut rng_b = ChaChaRng::from_seed([12u8; 32]);
+    audit.reprove_incremental_with_rng(&mut account, &mut rng_b)
+         .unwrap();
+
+    // The three original entries' commitments must be byte-for-byte unchanged -- if they'd been
+    // resampled under rng_b they'd almost certainly differ -- while the new fourth entry gets a
+    // commitment of its own, and the untouched liabilities are unchanged entirely
+    let new_asset_commitments = account.hidden_assets_commitments.clone().unwrap();
+    assert_eq!(new_asset_commitments.len(), 4);
+    assert_eq!(&new_asset_commitments[..3], &original_asset_commitments[..]);
+    assert_eq!(account.hidden_liabilities_commitments.clone().unwrap(),
+               original_liability_commitments);
+
+    // The incrementally re-proved account still verifies
+    audit.verify_solvency(&account).unwrap();
+  }
 }
\ No newline at end of file
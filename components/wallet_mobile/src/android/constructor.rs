@@ -1,6 +1,8 @@
+use super::exception::unwrap_exc_or_default;
+use super::hd_key;
 use crate::rust::types;
-use jni::objects::JClass;
-use jni::sys::{jbyteArray, jlong};
+use jni::objects::{JClass, JString};
+use jni::sys::{jbyteArray, jint, jlong};
 use jni::JNIEnv;
 use rand_chacha::ChaChaRng;
 use rand_core::SeedableRng;
@@ -13,41 +15,129 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_xfrKeyPairNew(
     _class: JClass,
     seed: jbyteArray,
 ) -> jlong {
-    let input = env.convert_byte_array(seed).unwrap();
-    let mut buf = [0u8; ASSET_TYPE_LENGTH];
-    buf.copy_from_slice(input.as_ref());
-    let mut prng = ChaChaRng::from_seed(buf);
-    let val = types::XfrKeyPair::from(RawXfrKeyPair::generate(&mut prng));
-    Box::into_raw(Box::new(val)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let input = env.convert_byte_array(seed).unwrap();
+        let mut buf = [0u8; ASSET_TYPE_LENGTH];
+        buf.copy_from_slice(input.as_ref());
+        let mut prng = ChaChaRng::from_seed(buf);
+        let val = types::XfrKeyPair::from(RawXfrKeyPair::generate(&mut prng));
+        Box::into_raw(Box::new(val)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Derives an `XfrKeyPair` at account/address_index `m/44'/917'/account'/0/address_index`
+/// from a raw backup seed, so a wallet can hold one seed and derive as many keypairs as it
+/// needs instead of backing up every keypair individually.
+/// @param {byte[]} seed - Backup seed bytes (arbitrary length; hashed into the master key).
+/// @param {number} account - Hardened account index.
+/// @param {number} address_index - Hardened address index within the account.
+pub unsafe extern "system" fn Java_com_findora_JniApi_xfrKeyPairFromSeed(
+    env: JNIEnv,
+    _class: JClass,
+    seed: jbyteArray,
+    account: jint,
+    address_index: jint,
+) -> jlong {
+    unwrap_exc_or_default(&env, || {
+        let input = env.convert_byte_array(seed).unwrap();
+        let child = hd_key::derive_child_secret(&input, account as u32, address_index as u32);
+        let mut prng = ChaChaRng::from_seed(child);
+        let val = types::XfrKeyPair::from(RawXfrKeyPair::generate(&mut prng));
+        Box::into_raw(Box::new(val)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Converts a BIP39-style mnemonic phrase to a seed and derives an `XfrKeyPair` from it at
+/// the same `m/44'/917'/account'/0/index` path as `xfrKeyPairFromSeed`, so a wallet can be
+/// fully recovered from a human-memorable phrase instead of a raw seed backup.
+/// @param {string} phrase - BIP39 mnemonic phrase.
+/// @param {string} passphrase - Optional extra passphrase ("25th word"); may be empty.
+/// @param {number} account - Hardened account index.
+/// @param {number} index - Hardened address index within the account.
+pub unsafe extern "system" fn Java_com_findora_JniApi_xfrKeyPairFromMnemonic(
+    env: JNIEnv,
+    _class: JClass,
+    phrase: JString,
+    passphrase: JString,
+    account: jint,
+    index: jint,
+) -> jlong {
+    unwrap_exc_or_default(&env, || {
+        let phrase: String = env
+            .get_string(phrase)
+            .expect("Couldn't get java string!")
+            .into();
+        let passphrase: String = env
+            .get_string(passphrase)
+            .expect("Couldn't get java string!")
+            .into();
+        let seed = hd_key::mnemonic_to_seed(&phrase, &passphrase);
+        let child = hd_key::derive_child_secret(&seed, account as u32, index as u32);
+        let mut prng = ChaChaRng::from_seed(child);
+        let val = types::XfrKeyPair::from(RawXfrKeyPair::generate(&mut prng));
+        Box::into_raw(Box::new(val)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Derives an `XfrKeyPair` deterministically from a human-memorable passphrase, so mobile
+/// clients can recover a wallet from a phrase rather than storing a raw seed. This is a
+/// single-phrase brain wallet (see `hd_key::brain_wallet_seed`), distinct from
+/// `xfrKeyPairFromMnemonic`'s BIP39-seed-plus-account/index derivation: the same phrase
+/// always yields exactly one keypair, with no account/address fan-out.
+/// @param {string} phrase - Human-memorable passphrase.
+pub unsafe extern "system" fn Java_com_findora_JniApi_xfrKeyPairFromPhrase(
+    env: JNIEnv,
+    _class: JClass,
+    phrase: JString,
+) -> jlong {
+    unwrap_exc_or_default(&env, || {
+        let phrase: String = env
+            .get_string(phrase)
+            .expect("Couldn't get java string!")
+            .into();
+        let seed = hd_key::brain_wallet_seed(&phrase);
+        let mut prng = ChaChaRng::from_seed(seed);
+        let val = types::XfrKeyPair::from(RawXfrKeyPair::generate(&mut prng));
+        Box::into_raw(Box::new(val)) as jlong
+    })
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn Java_com_findora_JniApi_xfrKeyPairDestroy(
-    _env: JNIEnv,
+    env: JNIEnv,
     _class: JClass,
     xfr_keypair_ptr: jlong,
 ) {
-    let _boxed_key = Box::from_raw(xfr_keypair_ptr as *mut types::XfrKeyPair);
+    unwrap_exc_or_default(&env, || {
+        let _boxed_key = Box::from_raw(xfr_keypair_ptr as *mut types::XfrKeyPair);
+    })
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn Java_com_findora_JniApi_authenticatedKVLookupNew(
-    _env: JNIEnv,
+    env: JNIEnv,
     _class: JClass,
 ) -> jlong {
-    unimplemented!()
-    // let val = types::AuthenticatedKVLookup{
-    // };
-    //
-    // Box::into_raw(Box::new(val)) as jlong
+    unwrap_exc_or_default(&env, || {
+        unimplemented!()
+        // let val = types::AuthenticatedKVLookup{
+        // };
+        //
+        // Box::into_raw(Box::new(val)) as jlong
+    })
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn Java_com_findora_JniApi_authenticatedKVLookupDestroy(
-    _env: JNIEnv,
+    env: JNIEnv,
     _class: JClass,
     authenticated_res_ptr: jlong,
 ) {
-    let _boxed_authenticated_res =
-        Box::from_raw(authenticated_res_ptr as *mut types::AuthenticatedKVLookup);
+    unwrap_exc_or_default(&env, || {
+        let _boxed_authenticated_res =
+            Box::from_raw(authenticated_res_ptr as *mut types::AuthenticatedKVLookup);
+    })
 }
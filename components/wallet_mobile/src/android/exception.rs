@@ -0,0 +1,50 @@
+use jni::errors::Error as JniError;
+use jni::JNIEnv;
+use std::any::Any;
+use std::panic::{catch_unwind, UnwindSafe};
+
+/// Runs `f` inside `catch_unwind` and converts any panic or `jni` error into a Java
+/// `RuntimeException`, returning `error_val` instead of unwinding across the FFI boundary.
+///
+/// Every JNI entry point in this crate uses raw pointer derefs and `.unwrap()`s liberally, so a
+/// bad input (null pointer, malformed JSON, a failing ledger call) used to unwind straight through
+/// the Rust/JVM boundary and abort the whole JVM. Wrapping each binding's body with this helper
+/// turns that into an ordinary Java exception instead.
+pub fn unwrap_exc_or<F, T>(env: &JNIEnv, f: F, error_val: T) -> T
+where
+    F: FnOnce() -> T + UnwindSafe,
+{
+    match catch_unwind(f) {
+        Ok(res) => res,
+        Err(cause) => {
+            throw(env, &describe_panic(cause));
+            error_val
+        }
+    }
+}
+
+/// Like `unwrap_exc_or`, but for entry points whose neutral failure value is `Default::default()`
+/// (0 for `jlong`/`jint`, `JNI_FALSE` for `jboolean`, null for `jstring`/`jobjectArray`).
+pub fn unwrap_exc_or_default<F, T>(env: &JNIEnv, f: F) -> T
+where
+    F: FnOnce() -> T + UnwindSafe,
+    T: Default,
+{
+    unwrap_exc_or(env, f, T::default())
+}
+
+fn describe_panic(cause: Box<dyn Any + Send>) -> String {
+    match cause.downcast_ref::<&str>() {
+        Some(s) => (*s).to_string(),
+        None => match cause.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => "unknown panic in JNI binding".to_string(),
+        },
+    }
+}
+
+fn throw(env: &JNIEnv, msg: &str) {
+    if let Err(JniError::JavaException) = env.throw_new("java/lang/RuntimeException", msg) {
+        // An exception is already pending (e.g. thrown while unwinding); nothing more to do.
+    }
+}
@@ -1,61 +1,94 @@
+use super::exception::unwrap_exc_or_default;
 use crate::rust::*;
 use credentials::{CredIssuerPublicKey, CredUserPublicKey};
-use jni::objects::{JClass, JString};
-use jni::sys::{jboolean, jint, jlong, jstring, JNI_TRUE};
+use jni::objects::{JClass, JObject, JString};
+use jni::sys::{jboolean, jint, jlong, jobjectArray, jstring, JNI_TRUE};
 use jni::JNIEnv;
 use zei::xfr::sig::XfrKeyPair;
 
+/// Builds a Java object array out of a Rust `Vec`, converting each element with `to_obj`.
+///
+/// This is the return-by-array counterpart of the `jlong`-boxing pattern used
+/// everywhere else in this file: Java has no way to receive an owned `Vec<T>`
+/// directly, so we allocate a `jobjectArray` of `element_class` and fill it one
+/// slot at a time.
+unsafe fn rust_vec_to_java<T>(
+    env: JNIEnv,
+    items: Vec<T>,
+    element_class: &str,
+    to_obj: impl Fn(&JNIEnv, T) -> jni::errors::Result<JObject>,
+) -> jobjectArray {
+    let class = env
+        .find_class(element_class)
+        .expect("Couldn't find Java array element class!");
+    let array = env
+        .new_object_array(items.len() as i32, class, JObject::null())
+        .expect("Couldn't allocate Java object array!");
+    for (idx, item) in items.into_iter().enumerate() {
+        let obj = to_obj(&env, item).expect("Couldn't convert Rust value to Java object!");
+        env.set_object_array_element(array, idx as i32, obj)
+            .expect("Couldn't set Java array element!");
+    }
+    array
+}
+
 #[no_mangle]
 /// @param am: amount to pay
 /// @param kp: owner's XfrKeyPair
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddFeeRelativeAuto(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     am: jint,
     kp: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let kp = &*(kp as *mut XfrKeyPair);
-    let builder = builder
-        .clone()
-        .add_fee_relative_auto(am as u64, kp.clone())
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let kp = &*(kp as *mut XfrKeyPair);
+        let builder = builder
+            .clone()
+            .add_fee_relative_auto(am as u64, kp.clone())
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
-// /// Use this func to get the necessary infomations for generating `Relative Inputs`
-// ///
-// /// - TxoRef::Relative("Element index of the result")
-// /// - ClientAssetRecord::from_json("Element of the result")
-// #[no_mangle]
-// pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderGetRelativeOutputs(
-//     env: JNIEnv,
-//     _: JClass,
-//     builder: jlong,
-// ) ->  Vec<ClientAssetRecord>  {
-//     let builder = &*(builder as *mut TransactionBuilder);
-//     let builder = builder.get_relative_outputs();
-//     // env.new_object_array()
-//     builder
-//
-//     // Box::into_raw(Box::new(builder)) as jlong
-//
-// }
+#[no_mangle]
+/// Use this func to get the necessary infomations for generating `Relative Inputs`
+///
+/// - TxoRef::Relative("Element index of the result")
+/// - ClientAssetRecord::from_json("Element of the result")
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderGetRelativeOutputs(
+    env: JNIEnv,
+    _: JClass,
+    builder: jlong,
+) -> jobjectArray {
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let outputs = builder.get_relative_outputs();
+        rust_vec_to_java(env, outputs, "java/lang/String", |env, record| {
+            let json =
+                serde_json::to_string(&record).expect("Couldn't serialize ClientAssetRecord!");
+            env.new_string(json).map(JObject::from)
+        })
+    })
+}
 
 #[no_mangle]
 /// As the last operation of any transaction,
 /// add a static fee to the transaction.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddFee(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     inputs: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let inputs = &*(inputs as *mut FeeInputs);
-    let builder = builder.clone().add_fee(inputs.clone()).unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let inputs = &*(inputs as *mut FeeInputs);
+        let builder = builder.clone().add_fee(inputs.clone()).unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -63,23 +96,27 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddFee(
 ///
 /// SEE [check_fee](ledger::data_model::Transaction::check_fee)
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderCheckFee(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
 ) -> jboolean {
-    let builder = &*(builder as *mut TransactionBuilder);
-    builder.check_fee() as jboolean
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        builder.check_fee() as jboolean
+    })
 }
 
 #[no_mangle]
 /// Create a new transaction builder.
 /// @param {BigInt} seq_id - Unique sequence ID to prevent replay attacks.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderNew(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     seq_id: jint,
 ) -> jlong {
-    Box::into_raw(Box::new(TransactionBuilder::new(seq_id as u64))) as jlong
+    unwrap_exc_or_default(&env, || {
+        Box::into_raw(Box::new(TransactionBuilder::new(seq_id as u64))) as jlong
+    })
 }
 
 #[no_mangle]
@@ -106,22 +143,24 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOpera
     token_code: JString,
     asset_rules: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let key_pair = &*(key_pair as *mut XfrKeyPair);
-    let memo: String = env
-        .get_string(memo)
-        .expect("Couldn't get java string!")
-        .into();
-    let token_code: String = env
-        .get_string(token_code)
-        .expect("Couldn't get java string!")
-        .into();
-    let asset_rules = &*(asset_rules as *mut AssetRules);
-    let builder = builder
-        .clone()
-        .add_operation_create_asset(key_pair, memo, token_code, asset_rules.clone())
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let key_pair = &*(key_pair as *mut XfrKeyPair);
+        let memo: String = env
+            .get_string(memo)
+            .expect("Couldn't get java string!")
+            .into();
+        let token_code: String = env
+            .get_string(token_code)
+            .expect("Couldn't get java string!")
+            .into();
+        let asset_rules = &*(asset_rules as *mut AssetRules);
+        let builder = builder
+            .clone()
+            .add_operation_create_asset(key_pair, memo, token_code, asset_rules.clone())
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -147,25 +186,27 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddBasic
     conf_amount: jboolean,
     zei_params: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let key_pair = &*(key_pair as *mut XfrKeyPair);
-    let code: String = env
-        .get_string(code)
-        .expect("Couldn't get java string!")
-        .into();
-    let zei_params = &*(zei_params as *mut PublicParams);
-    let builder = builder
-        .clone()
-        .add_basic_issue_asset(
-            key_pair,
-            code,
-            seq_num as u64,
-            amount as u64,
-            conf_amount == JNI_TRUE,
-            zei_params,
-        )
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let key_pair = &*(key_pair as *mut XfrKeyPair);
+        let code: String = env
+            .get_string(code)
+            .expect("Couldn't get java string!")
+            .into();
+        let zei_params = &*(zei_params as *mut PublicParams);
+        let builder = builder
+            .clone()
+            .add_basic_issue_asset(
+                key_pair,
+                code,
+                seq_num as u64,
+                amount as u64,
+                conf_amount == JNI_TRUE,
+                zei_params,
+            )
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -179,7 +220,7 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddBasic
 /// @see {@link module:Findora-Wasm.wasm_credential_commit|wasm_credential_commit} for information about how to generate a credential
 /// commitment.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOperationAirAssign(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     key_pair: jlong,
@@ -188,23 +229,25 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOpera
     commitment: jlong,
     pok: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let key_pair = &*(key_pair as *mut XfrKeyPair);
-    let user_public_key = &*(user_public_key as *mut CredUserPublicKey);
-    let issuer_public_key = &*(issuer_public_key as *mut CredIssuerPublicKey);
-    let commitment = &*(commitment as *mut CredentialCommitment);
-    let pok = &*(pok as *mut CredentialPoK);
-    let builder = builder
-        .clone()
-        .add_operation_air_assign(
-            key_pair,
-            user_public_key,
-            issuer_public_key,
-            commitment,
-            pok,
-        )
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let key_pair = &*(key_pair as *mut XfrKeyPair);
+        let user_public_key = &*(user_public_key as *mut CredUserPublicKey);
+        let issuer_public_key = &*(issuer_public_key as *mut CredIssuerPublicKey);
+        let commitment = &*(commitment as *mut CredentialCommitment);
+        let pok = &*(pok as *mut CredentialPoK);
+        let builder = builder
+            .clone()
+            .add_operation_air_assign(
+                key_pair,
+                user_public_key,
+                issuer_public_key,
+                commitment,
+                pok,
+            )
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -216,21 +259,23 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOpera
 /// transaction validates.
 /// @param {BigInt} seq_num - Nonce to prevent replays.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOperationKvUpdateNoHash(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     auth_key_pair: jlong,
     key: jlong,
     seq_num: u64,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let auth_key_pair = &*(auth_key_pair as *mut XfrKeyPair);
-    let key = &*(key as *mut Key);
-    let builder = builder
-        .clone()
-        .add_operation_kv_update_no_hash(auth_key_pair, key, seq_num as u64)
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let auth_key_pair = &*(auth_key_pair as *mut XfrKeyPair);
+        let key = &*(key as *mut Key);
+        let builder = builder
+            .clone()
+            .add_operation_kv_update_no_hash(auth_key_pair, key, seq_num as u64)
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -243,7 +288,7 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOpera
 /// @param {KVHash} hash - The hash to add to the custom data store.
 /// @param {BigInt} seq_num - Nonce to prevent replays.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOperationKvUpdateWithHash(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     auth_key_pair: jlong,
@@ -251,15 +296,17 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOpera
     seq_num: jint,
     kv_hash: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let auth_key_pair = &*(auth_key_pair as *mut XfrKeyPair);
-    let key = &*(key as *mut Key);
-    let kv_hash = &*(kv_hash as *mut KVHash);
-    let builder = builder
-        .clone()
-        .add_operation_kv_update_with_hash(auth_key_pair, key, seq_num as u64, kv_hash)
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let auth_key_pair = &*(auth_key_pair as *mut XfrKeyPair);
+        let key = &*(key as *mut Key);
+        let kv_hash = &*(kv_hash as *mut KVHash);
+        let builder = builder
+            .clone()
+            .add_operation_kv_update_with_hash(auth_key_pair, key, seq_num as u64, kv_hash)
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -279,21 +326,23 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddOpera
     code: JString,
     new_memo: JString,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let auth_key_pair = &*(auth_key_pair as *mut XfrKeyPair);
-    let code: String = env
-        .get_string(code)
-        .expect("Couldn't get java string!")
-        .into();
-    let new_memo: String = env
-        .get_string(new_memo)
-        .expect("Couldn't get java string!")
-        .into();
-    let builder = builder
-        .clone()
-        .add_operation_update_memo(auth_key_pair, code, new_memo)
-        .unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let auth_key_pair = &*(auth_key_pair as *mut XfrKeyPair);
+        let code: String = env
+            .get_string(code)
+            .expect("Couldn't get java string!")
+            .into();
+        let new_memo: String = env
+            .get_string(new_memo)
+            .expect("Couldn't get java string!")
+            .into();
+        let builder = builder
+            .clone()
+            .add_operation_update_memo(auth_key_pair, code, new_memo)
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -307,26 +356,188 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddTrans
     builder: jlong,
     op: JString,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let op: String = env
-        .get_string(op)
-        .expect("Couldn't get java string!")
-        .into();
-    let builder = builder.clone().add_transfer_operation(op).unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let op: String = env
+            .get_string(op)
+            .expect("Couldn't get java string!")
+            .into();
+        let builder = builder.clone().add_transfer_operation(op).unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Consolidates an owner's non-confidential UTXOs into a single confidential output owned by the
+/// same key, mirroring the auto-shielding sweep flow used in wallet SDKs.
+/// @param {XfrKeyPair} kp - Owner of the UTXOs being swept.
+/// @param {string} utxos - JSON-serialized array of `(TxoRef, ClientAssetRecord, Option<OwnerMemo>)`
+/// triples describing the owner's spendable non-confidential outputs.
+/// @param {BigInt} dust_threshold - Asset groups whose summed amount (minus fee) does not exceed
+/// this threshold are left untouched.
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddSweepToConfidential(
+    env: JNIEnv,
+    _: JClass,
+    builder: jlong,
+    kp: jlong,
+    utxos: JString,
+    dust_threshold: jint,
+) -> jlong {
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let kp = &*(kp as *mut XfrKeyPair);
+        let utxos: String = env
+            .get_string(utxos)
+            .expect("Couldn't get java string!")
+            .into();
+        let utxos: Vec<(TxoRef, ClientAssetRecord, Option<OwnerMemo>)> =
+            serde_json::from_str(&utxos).expect("Couldn't deserialize swept UTXO list!");
+        let builder = builder
+            .clone()
+            .add_sweep_to_confidential(kp, utxos, dust_threshold as u64)
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Attaches a short encrypted note to an already-added output, encrypted to the output's owner.
+/// @param {number} idx - Output to attach the memo to. Outputs are added sequentially.
+/// @param {string} memo - Plaintext UTF-8 memo to encrypt and store alongside the owner memo.
+/// @param {XfrKeyPair} recipient - Output owner's key pair; the memo is encrypted to its public key.
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAddMemoToOutput(
+    env: JNIEnv,
+    _: JClass,
+    builder: jlong,
+    idx: jint,
+    memo: JString,
+    recipient: jlong,
+) -> jlong {
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let memo: String = env
+            .get_string(memo)
+            .expect("Couldn't get java string!")
+            .into();
+        let recipient = &*(recipient as *mut XfrKeyPair);
+        let builder = builder
+            .clone()
+            .add_memo_to_output(idx as usize, &memo, recipient)
+            .unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Decrypts and returns the memo attached to an output, or an empty string when absent.
+/// @param {number} idx - Output whose memo should be decrypted.
+/// @param {XfrKeyPair} kp - Key pair able to decrypt the memo (sender or recipient).
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderGetOutputMemoUtf8(
+    env: JNIEnv,
+    _: JClass,
+    builder: jlong,
+    idx: jint,
+    kp: jlong,
+) -> jstring {
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let kp = &*(kp as *mut XfrKeyPair);
+        let memo = builder
+            .get_output_memo_utf8(idx as usize, kp)
+            .unwrap_or_default();
+        let output = env
+            .new_string(memo)
+            .expect("Couldn't create java string!");
+        output.into_inner()
+    })
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderSign(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     kp: jlong,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let kp = &*(kp as *mut XfrKeyPair);
-    let builder = builder.clone().sign(kp).unwrap();
-    Box::into_raw(Box::new(builder)) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let kp = &*(kp as *mut XfrKeyPair);
+        let builder = builder.clone().sign(kp).unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
+}
+
+#[no_mangle]
+/// Finalizes the unsigned transaction body and returns a deterministic signing payload alongside
+/// the current per-key spending counter, as a JSON string `{payload, counter}`.
+///
+/// This is the first step of detached-witness signing: the payload can be handed to a hardware
+/// wallet or a remote co-signer without exposing the builder itself, unlike `transactionBuilderSign`
+/// which folds the signature straight into the builder.
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderSigningPayload(
+    env: JNIEnv,
+    _: JClass,
+    builder: jlong,
+    kp: jlong,
+) -> jstring {
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let kp = &*(kp as *mut XfrKeyPair);
+        let (payload, counter) = builder.signing_payload(kp);
+        let json =
+            serde_json::to_string(&(payload, counter)).expect("Couldn't serialize payload!");
+        let output = env
+            .new_string(json)
+            .expect("Couldn't create java string!");
+        output.into_inner()
+    })
+}
+
+#[no_mangle]
+/// Produces a standalone witness over a signing payload without mutating any builder, so a
+/// hardware wallet or offline signer can co-sign without ever holding the transaction.
+/// @param {XfrKeyPair} kp - Key pair producing the witness.
+/// @param {string} payload - Signing payload returned by `transactionBuilderSigningPayload`.
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderComputeWitness(
+    env: JNIEnv,
+    _: JClass,
+    kp: jlong,
+    payload: JString,
+) -> jstring {
+    unwrap_exc_or_default(&env, || {
+        let kp = &*(kp as *mut XfrKeyPair);
+        let payload: String = env
+            .get_string(payload)
+            .expect("Couldn't get java string!")
+            .into();
+        let witness = kp.compute_witness(&payload);
+        let output = env
+            .new_string(serde_json::to_string(&witness).expect("Couldn't serialize witness!"))
+            .expect("Couldn't create java string!");
+        output.into_inner()
+    })
+}
+
+#[no_mangle]
+/// Attaches a previously-produced detached witness to the builder, completing a multi-party or
+/// offline signing round without ever handing the unsigned transaction to the signer directly.
+/// @param {string} witness - Serialized witness produced by `transactionBuilderComputeWitness`.
+pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderAttachWitness(
+    env: JNIEnv,
+    _: JClass,
+    builder: jlong,
+    witness: JString,
+) -> jlong {
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let witness: String = env
+            .get_string(witness)
+            .expect("Couldn't get java string!")
+            .into();
+        let witness = serde_json::from_str(&witness).expect("Couldn't deserialize witness!");
+        let builder = builder.clone().attach_witness(witness).unwrap();
+        Box::into_raw(Box::new(builder)) as jlong
+    })
 }
 
 #[no_mangle]
@@ -336,11 +547,13 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderTransact
     _: JClass,
     builder: jlong,
 ) -> jstring {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let output = env
-        .new_string(builder.transaction())
-        .expect("Couldn't create java string!");
-    output.into_inner()
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let output = env
+            .new_string(builder.transaction())
+            .expect("Couldn't create java string!");
+        output.into_inner()
+    })
 }
 
 #[no_mangle]
@@ -350,35 +563,41 @@ pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderTransact
     _: JClass,
     builder: jlong,
 ) -> jstring {
-    let builder = &*(builder as *mut TransactionBuilder);
-    let output = env
-        .new_string(builder.transaction_handle())
-        .expect("Couldn't create java string!");
-    output.into_inner()
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        let output = env
+            .new_string(builder.transaction_handle())
+            .expect("Couldn't create java string!");
+        output.into_inner()
+    })
 }
 
 #[no_mangle]
 /// Fetches a client record from a transaction.
 /// @param {number} idx - Record to fetch. Records are added to the transaction builder sequentially.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderGetOwnerRecord(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     idx: jint,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    Box::into_raw(Box::new(builder.get_owner_record(idx as usize))) as jlong
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        Box::into_raw(Box::new(builder.get_owner_record(idx as usize))) as jlong
+    })
 }
 
 #[no_mangle]
 /// Fetches an owner memo from a transaction
 /// @param {number} idx - Owner memo to fetch. Owner memos are added to the transaction builder sequentially.
 pub unsafe extern "system" fn Java_com_findora_JniApi_transactionBuilderGetOwnerMemo(
-    _env: JNIEnv,
+    env: JNIEnv,
     _: JClass,
     builder: jlong,
     idx: jint,
 ) -> jlong {
-    let builder = &*(builder as *mut TransactionBuilder);
-    Box::into_raw(Box::new(builder.get_owner_memo(idx as usize))) as jlong
-}
\ No newline at end of file
+    unwrap_exc_or_default(&env, || {
+        let builder = &*(builder as *mut TransactionBuilder);
+        Box::into_raw(Box::new(builder.get_owner_memo(idx as usize))) as jlong
+    })
+}
@@ -4,17 +4,25 @@
 // To compile wasm package, run wasm-pack build in the wasm directory;
 #![deny(warnings)]
 use crate::wasm_data_model::*;
+use bip39::Mnemonic;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{Key as AeadKey, XChaCha20Poly1305, XNonce};
 use credentials::{
   credential_commit, credential_issuer_key_gen, credential_reveal, credential_sign,
   credential_user_key_gen, credential_verify, credential_verify_commitment, CredIssuerPublicKey,
   CredIssuerSecretKey, CredUserPublicKey, CredUserSecretKey, Credential as PlatformCredential,
 };
 use cryptohash::sha256;
+use hmac::{Hmac, Mac};
 use js_sys::Promise;
 use ledger::data_model::{b64enc, AssetTypeCode, AuthenticatedTransaction, Operation};
 use ledger::policies::{DebtMemo, Fraction};
 use rand_chacha::ChaChaRng;
 use rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::str;
 use txn_builder::{
   BuildsTransactions, PolicyChoice, TransactionBuilder as PlatformTransactionBuilder,
@@ -23,9 +31,10 @@ use txn_builder::{
 use util::error_to_jsvalue;
 use utils::HashOf;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::future_to_promise;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{Request, RequestInit, RequestMode};
+use web_sys::{Request, RequestInit, RequestMode, Response};
 
 use zei::serialization::ZeiFromToBytes;
 use zei::xfr::asset_record::{open_blind_asset_record as open_bar, AssetRecordType};
@@ -57,6 +66,15 @@ pub fn asset_type_from_jsvalue(val: &JsValue) -> Result<String, JsValue> {
   Ok(AssetTypeCode { val: code }.to_base64())
 }
 
+#[wasm_bindgen]
+/// Same as {@link asset_type_from_jsvalue}, but converts `val` with `serde-wasm-bindgen`
+/// instead of round-tripping through `into_serde`, so callers can hand over a plain JS array
+/// of bytes without it first being re-parsed as JSON.
+pub fn asset_type_from_jsvalue_direct(val: JsValue) -> Result<String, JsValue> {
+  let code: [u8; 16] = serde_wasm_bindgen::from_value(val).map_err(error_to_jsvalue)?;
+  Ok(AssetTypeCode { val: code }.to_base64())
+}
+
 #[wasm_bindgen]
 /// Given a serialized state commitment and transaction, returns true if the transaction correctly
 /// hashes up to the state commitment and false otherwise.
@@ -149,6 +167,80 @@ pub fn create_debt_memo(ir_numerator: u64,
   Ok(serde_json::to_string(&memo).unwrap())
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+/// A release predicate for a conditional/time-locked transfer, as committed by
+/// {@link TransactionBuilder#add_operation_conditional_transfer}. `And`/`Or` nest two
+/// sub-conditions, so arbitrary predicate trees (e.g. "after `t` OR approver signs") can be
+/// expressed.
+pub enum ConditionalTransferCondition {
+  After { timestamp: u64 },
+  Signature { pubkey: XfrPublicKey },
+  And {
+    left: Box<ConditionalTransferCondition>,
+    right: Box<ConditionalTransferCondition>,
+  },
+  Or {
+    left: Box<ConditionalTransferCondition>,
+    right: Box<ConditionalTransferCondition>,
+  },
+}
+
+impl ConditionalTransferCondition {
+  /// Evaluates this predicate tree against the current time and the public keys of approvers
+  /// who have signed off on release so far.
+  fn is_satisfied(&self, now: u64, approvals: &[XfrPublicKey]) -> bool {
+    match self {
+      ConditionalTransferCondition::After { timestamp } => now >= *timestamp,
+      ConditionalTransferCondition::Signature { pubkey } => approvals.contains(pubkey),
+      ConditionalTransferCondition::And { left, right } => {
+        left.is_satisfied(now, approvals) && right.is_satisfied(now, approvals)
+      }
+      ConditionalTransferCondition::Or { left, right } => {
+        left.is_satisfied(now, approvals) || right.is_satisfied(now, approvals)
+      }
+    }
+  }
+}
+
+#[wasm_bindgen]
+/// Client-side check of whether `condition_json` (a {@link ConditionalTransferCondition} tree)
+/// is satisfied given `now` (a Unix timestamp) and the public keys of those who have already
+/// signed off on release. This is advisory only -- see the note on
+/// {@link TransactionBuilder#add_operation_conditional_transfer} for why the ledger itself
+/// doesn't evaluate or enforce this tree in this tree's current form.
+/// @param {string} condition_json - JSON-serialized {@link ConditionalTransferCondition}.
+/// @param {BigInt} now - Current Unix timestamp.
+/// @param {JsValue} approvals - Array of `XfrPublicKey`s that have signed off on release.
+pub fn conditional_transfer_is_satisfied(condition_json: String,
+                                        now: u64,
+                                        approvals: JsValue)
+                                        -> Result<bool, JsValue> {
+  let condition: ConditionalTransferCondition =
+    serde_json::from_str(&condition_json).map_err(error_to_jsvalue)?;
+  let approvals: Vec<XfrPublicKey> = approvals.into_serde().map_err(error_to_jsvalue)?;
+  Ok(condition.is_satisfied(now, &approvals))
+}
+
+#[wasm_bindgen]
+/// Produces the approver's signature satisfying a `Signature(pubkey)` leg of a
+/// {@link ConditionalTransferCondition}. The signed message binds the approval to the exact
+/// condition and KV sequence number it was committed under, so it can't be replayed against a
+/// different escrow. Delivering this signature to whoever submits the release -- and checking it
+/// against `approver_key_pair`'s public key with {@link conditional_transfer_is_satisfied} -- is
+/// the caller's responsibility; see {@link TransactionBuilder#add_operation_conditional_transfer}
+/// for why this tree can't have the ledger do that automatically.
+/// @param {XfrKeyPair} approver_key_pair - The approver named in the `Signature(pubkey)` leg.
+/// @param {string} condition_json - JSON-serialized {@link ConditionalTransferCondition} being approved.
+/// @param {BigInt} seq_num - KV sequence number the condition was committed under.
+pub fn release_conditional(approver_key_pair: &XfrKeyPair,
+                          condition_json: String,
+                          seq_num: u64)
+                          -> Result<JsValue, JsValue> {
+  let message = format!("{}:{}", condition_json, seq_num);
+  sign(approver_key_pair, message)
+}
+
 #[wasm_bindgen]
 /// Structure that allows users to construct arbitrary transactions.
 pub struct TransactionBuilder {
@@ -321,6 +413,44 @@ impl TransactionBuilder {
     Ok(self)
   }
 
+  /// Commits a conditional/time-locked transfer's release predicate to the ledger, for escrow
+  /// and milestone-based loan disbursement flows. `condition_json` deserializes into a
+  /// {@link ConditionalTransferCondition} tree (`After(timestamp)`, `Signature(pubkey)`, or an
+  /// `And`/`Or` of two sub-conditions); `kv_hash` must commit to its serialized bytes, exactly as
+  /// {@link TransactionBuilder#add_operation_kv_update_with_hash} commits to any other off-chain
+  /// payload.
+  ///
+  /// NOTE: this tree's ledger crate doesn't vendor an operation kind that locks a transfer's
+  /// inputs until a predicate evaluates true at apply time -- the generic KV-update operation is
+  /// the closest existing primitive, and it can only commit the predicate alongside the
+  /// transaction, not make the ledger refuse to apply the accompanying transfer until the
+  /// predicate holds. Use {@link conditional_transfer_is_satisfied}/{@link release_conditional}
+  /// to gate *when a wallet submits* that transfer, which is the best this client can enforce
+  /// without ledger-side support for the predicate itself.
+  /// @param {XfrKeyPair} auth_key_pair - Key pair authorizing this KV commitment.
+  /// @param {Key} key - KV key the commitment is stored under.
+  /// @param {BigInt} seq_num - Sequence number for this KV key.
+  /// @param {KVHash} kv_hash - Hash committing to `condition_json`'s serialized bytes.
+  /// @param {string} condition_json - JSON-serialized {@link ConditionalTransferCondition}.
+  /// @throws Will throw an error if `condition_json` fails to deserialize.
+  pub fn add_operation_conditional_transfer(mut self,
+                                            auth_key_pair: &XfrKeyPair,
+                                            key: &Key,
+                                            seq_num: u64,
+                                            kv_hash: KVHash,
+                                            condition_json: String)
+                                            -> Result<TransactionBuilder, JsValue> {
+    let _condition: ConditionalTransferCondition =
+      serde_json::from_str(&condition_json).map_err(error_to_jsvalue)?;
+    self.get_builder_mut()
+        .add_operation_kv_update(auth_key_pair,
+                                 key.get_ref(),
+                                 seq_num,
+                                 Some(&kv_hash.get_hash()))
+        .map_err(error_to_jsvalue)?;
+    Ok(self)
+  }
+
   /// Adds an `UpdateMemo` operation to a WasmTransactionBuilder with the given memo
   pub fn add_operation_update_memo(mut self,
                                    auth_key_pair: &XfrKeyPair,
@@ -347,6 +477,17 @@ impl TransactionBuilder {
     Ok(self)
   }
 
+  /// Same as {@link TransactionBuilder#add_operation}, but takes `op` as a `serde-wasm-bindgen`
+  /// value instead of a JSON string, so callers already holding a structured JS operation object
+  /// don't have to stringify and re-parse it.
+  /// @param {JsValue} op - a structured operation object (i.e. a transfer operation).
+  /// @throws Will throw an error if `op` fails to deserialize.
+  pub fn add_operation_direct(mut self, op: JsValue) -> Result<TransactionBuilder, JsValue> {
+    let op: Operation = serde_wasm_bindgen::from_value(op).map_err(error_to_jsvalue)?;
+    self.get_builder_mut().add_operation(op);
+    Ok(self)
+  }
+
   pub fn sign(mut self, kp: &XfrKeyPair) -> Result<TransactionBuilder, JsValue> {
     self.get_builder_mut().sign(kp);
     Ok(self)
@@ -360,6 +501,13 @@ impl TransactionBuilder {
            .map_err(error_to_jsvalue)?)
   }
 
+  /// Same as {@link TransactionBuilder#transaction}, but hands back the transaction as a
+  /// structured `serde-wasm-bindgen` value instead of a JSON string, so callers can read it
+  /// without a second parse pass.
+  pub fn transaction_direct(&self) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(self.get_builder().transaction()).map_err(error_to_jsvalue)
+  }
+
   /// Fetches a client record from a transaction.
   /// @param {number} idx - Record to fetch. Records are added to the transaction builder sequentially.
   pub fn get_owner_record(&self, idx: usize) -> ClientAssetRecord {
@@ -379,6 +527,10 @@ impl TransactionBuilder {
 /// Structure that enables clients to construct complex transfers.
 pub struct TransferOperationBuilder {
   op_builder: PlatformTransferOperationBuilder,
+  // Per-asset-type running totals for inputs/outputs added via `add_input_mixed`/
+  // `add_output_mixed`, so `create_mixed_transfer` can check they balance per type.
+  mixed_inputs: Vec<(String, u64)>,
+  mixed_outputs: Vec<(String, u64)>,
 }
 
 impl TransferOperationBuilder {
@@ -456,6 +608,12 @@ impl TransferOperationBuilder {
     serde_json::to_string(&self.op_builder).unwrap()
   }
 
+  /// Same as {@link TransferOperationBuilder#debug}, but returns a structured
+  /// `serde-wasm-bindgen` value instead of a JSON string.
+  pub fn debug_direct(&self) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&self.op_builder).map_err(error_to_jsvalue)
+  }
+
   /// Wraps around TransferOperationBuilder to add an input to a transfer operation builder.
   /// @param {TxoRef} txo_ref - Absolute or relative utxo reference
   /// @param {string} asset_record - Serialized client asset record to serve as transfer input. This record must exist on the
@@ -507,6 +665,30 @@ impl TransferOperationBuilder {
     self.add_input(txo_ref, asset_record, owner_memo, None, key, amount)
   }
 
+  /// Adds an input to a multi-asset-type ("asset mixing") transfer. Unlike
+  /// {@link TransferOperationBuilder#add_input_no_tracking}, this records the input's asset
+  /// type and amount so {@link TransferOperationBuilder#create_mixed_transfer} can check that
+  /// every asset type balances, since a mixed transfer may move several distinct asset types in
+  /// one operation.
+  /// @see {@link TransferOperationBuilder#add_input_no_tracking} for parameter details.
+  pub fn add_input_mixed(self,
+                         txo_ref: TxoRef,
+                         asset_record: ClientAssetRecord,
+                         owner_memo: Option<OwnerMemo>,
+                         tracing_policies: Option<&TracingPolicies>,
+                         key: &XfrKeyPair,
+                         amount: u64)
+                         -> Result<TransferOperationBuilder, JsValue> {
+    let oar =
+      open_bar(asset_record.get_bar_ref(),
+               &owner_memo.as_ref().map(|memo| memo.get_memo_ref().clone()),
+               key.get_sk_ref()).map_err(|_e| JsValue::from_str("Could not open asset record"))?;
+    let code = AssetTypeCode { val: oar.asset_type }.to_base64();
+    let mut builder = self.add_input(txo_ref, asset_record, owner_memo, tracing_policies, key, amount)?;
+    builder.mixed_inputs.push((code, amount));
+    Ok(builder)
+  }
+
   /// Wraps around TransferOperationBuilder to add an output to a transfer operation builder.
   ///
   /// @param {BigInt} amount - amount to transfer to the recipient.
@@ -551,6 +733,80 @@ impl TransferOperationBuilder {
     self.add_output(amount, recipient, None, code, conf_amount, conf_type)
   }
 
+  /// Adds an output to a multi-asset-type ("asset mixing") transfer. Unlike
+  /// {@link TransferOperationBuilder#add_output_no_tracking}, this records the output's asset
+  /// type and amount so {@link TransferOperationBuilder#create_mixed_transfer} can check that
+  /// every asset type balances.
+  /// @see {@link TransferOperationBuilder#add_output_no_tracking} for parameter details.
+  pub fn add_output_mixed(self,
+                          amount: u64,
+                          recipient: &XfrPublicKey,
+                          tracing_policies: Option<&TracingPolicies>,
+                          code: String,
+                          conf_amount: bool,
+                          conf_type: bool)
+                          -> Result<TransferOperationBuilder, JsValue> {
+    let mut builder = self.add_output(amount,
+                                      recipient,
+                                      tracing_policies,
+                                      code.clone(),
+                                      conf_amount,
+                                      conf_type)?;
+    builder.mixed_outputs.push((code, amount));
+    Ok(builder)
+  }
+
+  /// One-shot helper that shields a transparent (nonconfidential) record: opens `asset_record`
+  /// with `key`, adds it as the sole input, and adds a single confidential output back to `key`
+  /// with both amount and asset type hidden. Equivalent to manually wiring
+  /// {@link TransferOperationBuilder#add_input_no_tracking} and
+  /// {@link TransferOperationBuilder#add_output_no_tracking} with
+  /// `AssetRecordType::from_booleans(true, true)`, but in one call.
+  /// @param {TxoRef} txo_ref - Absolute or relative reference to the record on the ledger.
+  /// @param {ClientAssetRecord} asset_record - The transparent record to shield.
+  /// @param {OwnerMemo} owner_memo - Opening parameters, if any.
+  /// @param {XfrKeyPair} key - Key pair that owns `asset_record` and receives the shielded output.
+  /// @throws Will throw an error if `asset_record` cannot be opened with `key`/`owner_memo`.
+  pub fn shield_record(self,
+                       txo_ref: TxoRef,
+                       asset_record: ClientAssetRecord,
+                       owner_memo: Option<OwnerMemo>,
+                       key: &XfrKeyPair)
+                       -> Result<TransferOperationBuilder, JsValue> {
+    let oar =
+      open_bar(asset_record.get_bar_ref(),
+               &owner_memo.as_ref().map(|memo| memo.get_memo_ref().clone()),
+               key.get_sk_ref()).map_err(|_e| JsValue::from_str("Could not open asset record"))?;
+    let amount = oar.amount;
+    let code = AssetTypeCode { val: oar.asset_type }.to_base64();
+    self.add_input_no_tracking(txo_ref, asset_record, owner_memo, key, amount)?
+        .add_output_no_tracking(amount, key.get_pk_ref(), code, true, true)
+  }
+
+  /// Inverse of {@link TransferOperationBuilder#shield_record}: opens a confidential
+  /// `asset_record` with `key` and adds a single fully transparent output back to `key`, moving
+  /// value out of the confidential pool.
+  /// @param {TxoRef} txo_ref - Absolute or relative reference to the record on the ledger.
+  /// @param {ClientAssetRecord} asset_record - The confidential record to unshield.
+  /// @param {OwnerMemo} owner_memo - Opening parameters.
+  /// @param {XfrKeyPair} key - Key pair that owns `asset_record` and receives the transparent output.
+  /// @throws Will throw an error if `asset_record` cannot be opened with `key`/`owner_memo`.
+  pub fn unshield_record(self,
+                        txo_ref: TxoRef,
+                        asset_record: ClientAssetRecord,
+                        owner_memo: Option<OwnerMemo>,
+                        key: &XfrKeyPair)
+                        -> Result<TransferOperationBuilder, JsValue> {
+    let oar =
+      open_bar(asset_record.get_bar_ref(),
+               &owner_memo.as_ref().map(|memo| memo.get_memo_ref().clone()),
+               key.get_sk_ref()).map_err(|_e| JsValue::from_str("Could not open asset record"))?;
+    let amount = oar.amount;
+    let code = AssetTypeCode { val: oar.asset_type }.to_base64();
+    self.add_input_no_tracking(txo_ref, asset_record, owner_memo, key, amount)?
+        .add_output_no_tracking(amount, key.get_pk_ref(), code, false, false)
+  }
+
   /// Wraps around TransferOperationBuilder to ensure the transfer inputs and outputs are balanced.
   /// This function will add change outputs for all unspent portions of input records.
   /// @throws Will throw an error if the transaction cannot be balanced.
@@ -576,6 +832,31 @@ impl TransferOperationBuilder {
     Ok(self)
   }
 
+  /// Finalizes a multi-asset-type ("asset mixing") transfer built with
+  /// {@link TransferOperationBuilder#add_input_mixed}/{@link
+  /// TransferOperationBuilder#add_output_mixed}. zei already produces a single `XfrBody`
+  /// covering every distinct asset type added to this operation; this checks that, for each
+  /// asset type, input and output amounts balance, since that per-type balance is what makes
+  /// the aggregated range-and-equality proof actually sound rather than merely balancing in
+  /// aggregate. It then finalizes exactly as {@link TransferOperationBuilder#create} does.
+  /// @throws Will throw an error if any asset type's inputs and outputs don't balance.
+  pub fn create_mixed_transfer(mut self,
+                               transfer_type: TransferType)
+                               -> Result<TransferOperationBuilder, JsValue> {
+    let mut input_totals: HashMap<String, u64> = HashMap::new();
+    for (code, amount) in &self.mixed_inputs {
+      *input_totals.entry(code.clone()).or_insert(0) += amount;
+    }
+    let mut output_totals: HashMap<String, u64> = HashMap::new();
+    for (code, amount) in &self.mixed_outputs {
+      *output_totals.entry(code.clone()).or_insert(0) += amount;
+    }
+    if input_totals != output_totals {
+      return Err(JsValue::from_str("Mixed transfer is not balanced per asset type"));
+    }
+    self.create(transfer_type)
+  }
+
   /// Wraps around TransferOperationBuilder to add a signature to the operation.
   ///
   /// All input owners must sign.
@@ -610,6 +891,240 @@ impl TransferOperationBuilder {
   }
 }
 
+#[derive(Serialize)]
+/// One transfer output in a {@link TransactionView}. `Viewable` records were opened by one of
+/// the keys the caller supplied; `Opaque` records are left undecrypted because none of those
+/// keys could open them.
+#[serde(tag = "status", rename_all = "snake_case")]
+enum ViewedOutput {
+  Viewable { amount: u64, asset_type: String, recipient: XfrPublicKey },
+  Opaque,
+}
+
+#[wasm_bindgen]
+/// A "confirm before sign" view over a transaction's transfer outputs, built by trial-opening
+/// each output's `BlindAssetRecord` against a set of viewing keys. Records a key can open come
+/// back with their decrypted amount, asset type, and recipient; records none of the supplied
+/// keys can open are left opaque rather than failing the whole view, since a wallet typically
+/// only holds the viewing keys relevant to its own outputs.
+pub struct TransactionView {
+  outputs: Vec<ViewedOutput>,
+}
+
+#[wasm_bindgen]
+impl TransactionView {
+  /// Builds a view over `builder`'s transfer outputs.
+  /// @param {TransactionBuilder} builder - Transaction builder whose transfer outputs to inspect.
+  /// @param {XfrKeyPair[]} viewing_keys - Keys to trial-open each output with.
+  pub fn new(builder: &TransactionBuilder, viewing_keys: Vec<XfrKeyPair>) -> TransactionView {
+    let mut outputs = vec![];
+    for op in builder.get_builder().transaction().body.operations.iter() {
+      if let Operation::TransferAsset(transfer) = op {
+        let xfr_body = &transfer.body.transfer;
+        for (idx, bar) in xfr_body.outputs.iter().enumerate() {
+          let owner_memo = xfr_body.owners_memos.get(idx).cloned().flatten();
+          let opened = viewing_keys.iter()
+                                   .find_map(|key| {
+                                     open_bar(bar, &owner_memo, key.get_sk_ref()).ok()
+                                   });
+          outputs.push(match opened {
+            Some(oar) => ViewedOutput::Viewable { amount: oar.amount,
+                                                  asset_type:
+                                                    AssetTypeCode { val: oar.asset_type }
+                                                                                      .to_base64(),
+                                                  recipient: bar.public_key },
+            None => ViewedOutput::Opaque,
+          });
+        }
+      }
+    }
+    TransactionView { outputs }
+  }
+
+  /// Serializes this view to a structured `serde-wasm-bindgen` value.
+  pub fn to_jsvalue(&self) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&self.outputs).map_err(error_to_jsvalue)
+  }
+}
+
+/// An owned UTXO tracked by a {@link WasmWallet}, already opened once so its amount and asset
+/// type don't need to be recomputed on every selection attempt.
+struct WalletUtxo {
+  txo_ref: TxoRef,
+  asset_record: ClientAssetRecord,
+  owner_memo: Option<OwnerMemo>,
+  amount: u64,
+  asset_type: String,
+}
+
+// No fee schedule is available to this wallet (the ledger doesn't expose one to wasm clients),
+// so this is a conservative placeholder allowing an exact-match search to accept a subset whose
+// sum lands a few units above the target rather than requiring bit-for-bit equality.
+const COIN_SELECTION_FEE_TOLERANCE: u64 = 0;
+
+/// Depth-first search over `candidates` (sorted ascending by amount) for a subset summing to
+/// within `[target, target + tolerance]`, pruning any branch whose running total would exceed
+/// that window. Returns the indices (into the original UTXO list) of the chosen subset.
+fn find_exact_subset(candidates: &[(usize, u64)], target: u64, tolerance: u64) -> Option<Vec<usize>> {
+  fn dfs(candidates: &[(usize, u64)],
+        pos: usize,
+        running: u64,
+        target: u64,
+        tolerance: u64,
+        chosen: &mut Vec<usize>)
+        -> bool {
+    if running >= target {
+      return running <= target + tolerance;
+    }
+    if pos == candidates.len() {
+      return false;
+    }
+    let (idx, amount) = candidates[pos];
+    if running + amount <= target + tolerance {
+      chosen.push(idx);
+      if dfs(candidates, pos + 1, running + amount, target, tolerance, chosen) {
+        return true;
+      }
+      chosen.pop();
+    }
+    dfs(candidates, pos + 1, running, target, tolerance, chosen)
+  }
+
+  let mut chosen = vec![];
+  if dfs(candidates, 0, 0, target, tolerance, &mut chosen) {
+    Some(chosen)
+  } else {
+    None
+  }
+}
+
+/// Greedily accumulates `candidates` (sorted descending by amount) until their sum meets or
+/// exceeds `target`. Used when no exact-match subset exists.
+fn select_greedy(candidates: &[(usize, u64)], target: u64) -> Option<Vec<usize>> {
+  let mut chosen = vec![];
+  let mut running = 0u64;
+  for &(idx, amount) in candidates {
+    if running >= target {
+      break;
+    }
+    chosen.push(idx);
+    running += amount;
+  }
+  if running >= target {
+    Some(chosen)
+  } else {
+    None
+  }
+}
+
+#[wasm_bindgen]
+#[derive(Default)]
+/// A client-side view of a wallet's owned UTXOs. Auto-selects inputs and computes change for a
+/// {@link TransferOperationBuilder} so callers don't have to hand-pick TXOs themselves.
+pub struct WasmWallet {
+  utxos: Vec<WalletUtxo>,
+}
+
+#[wasm_bindgen]
+impl WasmWallet {
+  /// Creates an empty wallet.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds an owned UTXO to the wallet, opening it with `key` to learn its amount and asset type.
+  /// @param {TxoRef} txo_ref - Absolute or relative reference to the UTXO on the ledger.
+  /// @param {ClientAssetRecord} asset_record - The UTXO's record.
+  /// @param {OwnerMemo} owner_memo - Opening parameters, if the record is confidential.
+  /// @param {XfrKeyPair} key - Key pair that owns this UTXO.
+  /// @throws Will throw an error if `asset_record` cannot be opened with `key`/`owner_memo`.
+  pub fn add_utxo(mut self,
+                  txo_ref: TxoRef,
+                  asset_record: ClientAssetRecord,
+                  owner_memo: Option<OwnerMemo>,
+                  key: &XfrKeyPair)
+                  -> Result<WasmWallet, JsValue> {
+    let oar =
+      open_bar(asset_record.get_bar_ref(),
+               &owner_memo.as_ref().map(|memo| memo.get_memo_ref().clone()),
+               key.get_sk_ref()).map_err(|_e| JsValue::from_str("Could not open asset record"))?;
+    self.utxos.push(WalletUtxo { txo_ref,
+                                 asset_record,
+                                 owner_memo,
+                                 amount: oar.amount,
+                                 asset_type: AssetTypeCode { val: oar.asset_type }.to_base64() });
+    Ok(self)
+  }
+
+  /// Returns the wallet's current balance of `code` (a base64 asset type code).
+  pub fn balance(&self, code: String) -> u64 {
+    self.utxos
+        .iter()
+        .filter(|utxo| utxo.asset_type == code)
+        .map(|utxo| utxo.amount)
+        .sum()
+  }
+
+  /// Selects UTXOs of `code` covering `amount`, then builds a transfer operation sending
+  /// `amount` to `recipient` and any leftover change back to `change_key`.
+  ///
+  /// Coin selection first runs a depth-first, branch-and-bound search over the wallet's `code`
+  /// UTXOs (sorted ascending by amount) for an exact-match subset, pruning any branch whose
+  /// running total would exceed `amount` plus a small fee tolerance. If no exact match exists,
+  /// it falls back to greedily accumulating the largest UTXOs first. The chosen input sum minus
+  /// `amount` becomes the change output, and only UTXOs of `code` are ever selected.
+  /// @param {XfrPublicKey} recipient - Public key to receive `amount`.
+  /// @param {XfrKeyPair} change_key - Key pair to receive any change, and to sign for each
+  /// selected input.
+  /// @param {string} code - Base64 asset type code to transfer.
+  /// @param {BigInt} amount - Amount to send to `recipient`.
+  /// @throws Will throw an error if the wallet's balance of `code` is insufficient.
+  pub fn build_transfer(mut self,
+                        recipient: &XfrPublicKey,
+                        change_key: &XfrKeyPair,
+                        code: String,
+                        amount: u64)
+                        -> Result<TransferOperationBuilder, JsValue> {
+    let mut ascending: Vec<(usize, u64)> = self.utxos
+                                               .iter()
+                                               .enumerate()
+                                               .filter(|(_, utxo)| utxo.asset_type == code)
+                                               .map(|(idx, utxo)| (idx, utxo.amount))
+                                               .collect();
+    ascending.sort_by_key(|(_, amount)| *amount);
+
+    let mut chosen = find_exact_subset(&ascending, amount, COIN_SELECTION_FEE_TOLERANCE).or_else(|| {
+      let mut descending = ascending.clone();
+      descending.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+      select_greedy(&descending, amount)
+    }).ok_or_else(|| JsValue::from_str("Insufficient balance for transfer"))?;
+
+    let total: u64 = chosen.iter().map(|&idx| self.utxos[idx].amount).sum();
+    let change = total - amount;
+
+    // Remove chosen UTXOs highest-index-first so earlier indices stay valid as we go.
+    chosen.sort_unstable_by_key(|&idx| std::cmp::Reverse(idx));
+    let mut builder = TransferOperationBuilder::new();
+    for idx in chosen {
+      let utxo = self.utxos.remove(idx);
+      builder = builder.add_input_no_tracking(utxo.txo_ref,
+                                              utxo.asset_record,
+                                              utxo.owner_memo,
+                                              change_key,
+                                              utxo.amount)?;
+    }
+    builder = builder.add_output_no_tracking(amount, recipient, code.clone(), false, false)?;
+    if change > 0 {
+      builder = builder.add_output_no_tracking(change,
+                                               change_key.get_pk_ref(),
+                                               code,
+                                               false,
+                                               false)?;
+    }
+    Ok(builder)
+  }
+}
+
 ///////////// CRYPTO //////////////////////
 #[wasm_bindgen]
 /// Returns a JsValue containing decrypted owner record information,
@@ -661,6 +1176,113 @@ pub fn new_keypair_from_seed(seed_str: String, name: Option<String>) -> XfrKeyPa
   XfrKeyPair::generate(&mut prng)
 }
 
+type HmacSha512 = Hmac<Sha512>;
+
+/// The offset added to a plain index to mark it as hardened, per SLIP-0010/BIP32. ed25519 only
+/// supports hardened derivation, so `keypair_from_mnemonic`'s path segments are all implicitly
+/// hardened.
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+#[wasm_bindgen]
+/// Generates a new BIP39 mnemonic phrase from fresh entropy, for use with
+/// {@link keypair_from_mnemonic}.
+/// @param {number} word_count - Number of words (12, 15, 18, 21, or 24).
+/// @throws Will throw an error if `word_count` is not a valid BIP39 word count.
+pub fn generate_mnemonic(word_count: usize) -> Result<String, JsValue> {
+  let mnemonic = Mnemonic::generate(word_count).map_err(|e| {
+                  JsValue::from_str(&format!("Could not generate mnemonic: {}", e))
+                })?;
+  Ok(mnemonic.to_string())
+}
+
+/// Parses one hardened path segment, e.g. `44` from `44'`. ed25519 only supports hardened
+/// derivation, so a segment without the `'` marker is rejected.
+fn parse_hardened_segment(segment: &str) -> Result<u32, JsValue> {
+  let index_str = segment.strip_suffix('\'').ok_or_else(|| {
+                    JsValue::from_str(&format!(
+      "Path segment '{}' is not hardened; ed25519 only supports hardened derivation", segment))
+                  })?;
+  index_str.parse::<u32>()
+           .map_err(|_e| JsValue::from_str(&format!("Invalid path segment '{}'", segment)))
+}
+
+/// Splits a derivation path like `m/44'/917'/0'/0'/0'` into its hardened indices, rejecting a
+/// path that doesn't start with `m`.
+fn parse_derivation_path(path: &str) -> Result<Vec<u32>, JsValue> {
+  let mut segments = path.split('/');
+  if segments.next() != Some("m") {
+    return Err(JsValue::from_str("Derivation path must start with 'm'"));
+  }
+  segments.map(parse_hardened_segment).collect()
+}
+
+/// One step of SLIP-0010 ed25519 child key derivation:
+/// `child = HMAC-SHA512(key = chain_code, data = 0x00 || parent_key || index_be)`, with the
+/// hardened offset folded into `index`. The left 32 bytes of the result become the child key,
+/// the right 32 the child chain code.
+fn derive_child(parent_key: &[u8; 32], chain_code: &[u8; 32], index: u32) -> ([u8; 32], [u8; 32]) {
+  let mut data = Vec::with_capacity(1 + 32 + 4);
+  data.push(0u8);
+  data.extend_from_slice(parent_key);
+  data.extend_from_slice(&(index | HARDENED_OFFSET).to_be_bytes());
+
+  let mut mac = HmacSha512::new_from_slice(chain_code).expect("HMAC accepts any key length");
+  mac.update(&data);
+  let result = mac.finalize().into_bytes();
+
+  let mut key = [0u8; 32];
+  let mut code = [0u8; 32];
+  key.copy_from_slice(&result[..32]);
+  code.copy_from_slice(&result[32..]);
+  (key, code)
+}
+
+#[wasm_bindgen]
+/// Derives an `XfrKeyPair` from a BIP39 mnemonic via SLIP-0010 ed25519 hierarchical
+/// derivation, so Findora keys are restorable from a standard seed phrase instead of the
+/// ad hoc seeding {@link new_keypair_from_seed} does.
+///
+/// 1. `phrase` is validated as a BIP39 mnemonic and expanded to a 64-byte seed via
+///    PBKDF2-HMAC-SHA512 (2048 iterations, salt `"mnemonic" + passphrase`).
+/// 2. The master key/chain code are `HMAC-SHA512(key = "ed25519 seed", data = seed)`.
+/// 3. Each hardened segment of `path` (e.g. `m/44'/917'/0'/0'/0'`) derives a child key/chain
+///    code per SLIP-0010; a non-hardened segment is rejected since ed25519 only supports
+///    hardened derivation.
+///
+/// The final 32-byte key seeds `XfrKeyPair::generate`, matching the seeding convention already
+/// used by {@link new_keypair_from_seed}.
+/// @param {string} phrase - BIP39 mnemonic phrase.
+/// @param {string} passphrase - Optional BIP39 passphrase (treated as empty if omitted).
+/// @param {string} path - SLIP-0010 derivation path, e.g. `m/44'/917'/0'/0'/0'`.
+/// @throws Will throw an error if `phrase` fails to validate or `path` contains a non-hardened
+/// segment.
+pub fn keypair_from_mnemonic(phrase: String,
+                            passphrase: Option<String>,
+                            path: String)
+                            -> Result<XfrKeyPair, JsValue> {
+  let mnemonic = Mnemonic::parse(&phrase).map_err(|e| {
+                  JsValue::from_str(&format!("Invalid mnemonic: {}", e))
+                })?;
+  let seed = mnemonic.to_seed(&passphrase.unwrap_or_default());
+
+  let mut mac = HmacSha512::new_from_slice(b"ed25519 seed").expect("HMAC accepts any key length");
+  mac.update(&seed);
+  let master = mac.finalize().into_bytes();
+  let mut key = [0u8; 32];
+  let mut chain_code = [0u8; 32];
+  key.copy_from_slice(&master[..32]);
+  chain_code.copy_from_slice(&master[32..]);
+
+  for index in parse_derivation_path(&path)? {
+    let (child_key, child_code) = derive_child(&key, &chain_code, index);
+    key = child_key;
+    chain_code = child_code;
+  }
+
+  let mut prng = ChaChaRng::from_seed(key);
+  Ok(XfrKeyPair::generate(&mut prng))
+}
+
 #[wasm_bindgen]
 /// Returns base64 encoded representation of an XfrPublicKey.
 pub fn public_key_to_base64(key: &XfrPublicKey) -> String {
@@ -687,6 +1309,126 @@ pub fn keypair_from_str(str: String) -> XfrKeyPair {
   XfrKeyPair::zei_from_bytes(&hex::decode(str).unwrap())
 }
 
+const KEYSTORE_VERSION: u32 = 1;
+const KEYSTORE_KDF: &str = "argon2id";
+const KEYSTORE_SALT_LEN: usize = 16;
+const KEYSTORE_NONCE_LEN: usize = 24;
+const KEYSTORE_TAG_LEN: usize = 16;
+const KEYSTORE_ARGON2ID_MEM_COST_KIB: u32 = 19_456;
+const KEYSTORE_ARGON2ID_TIME_COST: u32 = 2;
+const KEYSTORE_ARGON2ID_PARALLELISM: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreKdfParams {
+  mem_cost_kib: u32,
+  time_cost: u32,
+  parallelism: u32,
+}
+
+/// The self-describing JSON envelope produced by `encrypt_keypair` and consumed by
+/// `decrypt_keypair`. All binary fields are base64-encoded.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystore {
+  version: u32,
+  kdf: String,
+  kdf_params: KeystoreKdfParams,
+  salt: String,
+  nonce: String,
+  ciphertext: String,
+  mac: String,
+}
+
+/// Derives a 32-byte symmetric key from `password` via Argon2id, using `salt` and `params`.
+fn derive_keystore_key(password: &str,
+                        salt: &[u8],
+                        params: &KeystoreKdfParams)
+                        -> Result<[u8; 32], JsValue> {
+  let argon2_params = argon2::Params::new(params.mem_cost_kib,
+                                           params.time_cost,
+                                           params.parallelism,
+                                           Some(32)).map_err(|e| {
+                         JsValue::from_str(&format!("Invalid Argon2id parameters: {}", e))
+                       })?;
+  let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, argon2_params);
+  let mut key = [0u8; 32];
+  argon2.hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| JsValue::from_str(&format!("Could not derive key from password: {}", e)))?;
+  Ok(key)
+}
+
+#[wasm_bindgen]
+/// Encrypts `kp` into a self-describing JSON keystore, safe to persist at rest (browser local
+/// storage, a downloaded file, etc) in place of `keypair_to_str`'s plaintext hex. A symmetric
+/// key is derived from `password` with Argon2id over a random salt, and the key pair's raw
+/// bytes are then encrypted under XChaCha20-Poly1305 with a random nonce.
+/// @param {XfrKeyPair} kp - Key pair to encrypt.
+/// @param {string} password - Password to encrypt the key pair under.
+/// @see {@link decrypt_keypair}
+pub fn encrypt_keypair(kp: &XfrKeyPair, password: String) -> Result<String, JsValue> {
+  let mut rng = rand::thread_rng();
+  let mut salt = [0u8; KEYSTORE_SALT_LEN];
+  rand_core::RngCore::fill_bytes(&mut rng, &mut salt);
+  let mut nonce_bytes = [0u8; KEYSTORE_NONCE_LEN];
+  rand_core::RngCore::fill_bytes(&mut rng, &mut nonce_bytes);
+
+  let kdf_params = KeystoreKdfParams { mem_cost_kib: KEYSTORE_ARGON2ID_MEM_COST_KIB,
+                                       time_cost: KEYSTORE_ARGON2ID_TIME_COST,
+                                       parallelism: KEYSTORE_ARGON2ID_PARALLELISM };
+  let key = derive_keystore_key(&password, &salt, &kdf_params)?;
+
+  let cipher = XChaCha20Poly1305::new(AeadKey::from_slice(&key));
+  let nonce = XNonce::from_slice(&nonce_bytes);
+  let mut sealed = cipher.encrypt(nonce, kp.zei_to_bytes().as_slice())
+                         .map_err(|_e| JsValue::from_str("Could not encrypt key pair"))?;
+  let mac = sealed.split_off(sealed.len() - KEYSTORE_TAG_LEN);
+
+  let keystore = EncryptedKeystore { version: KEYSTORE_VERSION,
+                                     kdf: KEYSTORE_KDF.to_string(),
+                                     kdf_params,
+                                     salt: base64::encode(&salt),
+                                     nonce: base64::encode(&nonce_bytes),
+                                     ciphertext: base64::encode(&sealed),
+                                     mac: base64::encode(&mac) };
+  serde_json::to_string(&keystore).map_err(|e| {
+                                     JsValue::from_str(&format!("Could not serialize keystore: {}", e))
+                                   })
+}
+
+#[wasm_bindgen]
+/// Decrypts a keystore produced by `encrypt_keypair`, re-deriving the key from `password` and
+/// verifying the AEAD tag before reconstructing the key pair. Fails closed on a wrong password
+/// or a tampered keystore -- both surface as the same generic error, so as not to help an
+/// attacker distinguish the two.
+/// @param {string} json - Keystore JSON produced by {@link encrypt_keypair}.
+/// @param {string} password - Password the keystore was encrypted under.
+/// @throws Will throw an error if `password` is incorrect or `json` is malformed or tampered
+/// with.
+/// @see {@link encrypt_keypair}
+pub fn decrypt_keypair(json: String, password: String) -> Result<XfrKeyPair, JsValue> {
+  let keystore: EncryptedKeystore =
+    serde_json::from_str(&json).map_err(|e| JsValue::from_str(&format!("Could not parse keystore: {}", e)))?;
+  if keystore.kdf != KEYSTORE_KDF {
+    return Err(JsValue::from_str(&format!("Unsupported keystore KDF '{}'", keystore.kdf)));
+  }
+
+  let salt = base64::decode(&keystore.salt).map_err(|_e| JsValue::from_str("Could not decode keystore salt"))?;
+  let nonce_bytes =
+    base64::decode(&keystore.nonce).map_err(|_e| JsValue::from_str("Could not decode keystore nonce"))?;
+  let mut sealed = base64::decode(&keystore.ciphertext).map_err(|_e| {
+                                    JsValue::from_str("Could not decode keystore ciphertext")
+                                  })?;
+  let mac =
+    base64::decode(&keystore.mac).map_err(|_e| JsValue::from_str("Could not decode keystore mac"))?;
+  sealed.extend_from_slice(&mac);
+
+  let key = derive_keystore_key(&password, &salt, &keystore.kdf_params)?;
+  let cipher = XChaCha20Poly1305::new(AeadKey::from_slice(&key));
+  let nonce = XNonce::from_slice(&nonce_bytes);
+  let plaintext = cipher.decrypt(nonce, sealed.as_slice())
+                        .map_err(|_e| JsValue::from_str("Incorrect password or corrupted keystore"))?;
+  Ok(XfrKeyPair::zei_from_bytes(&plaintext))
+}
+
 #[wasm_bindgen]
 /// Returns the SHA256 signature of the given string as a hex-encoded
 /// string.
@@ -722,7 +1464,7 @@ pub fn sign(key_pair: &XfrKeyPair, message: String) -> Result<JsValue, JsValue>
 /// @param {string} transaction_str - JSON-encoded transaction string.
 ///
 /// @see {@link get_txn_status} for information about transaction statuses.
-// TODO Design and implement a notification mechanism.
+/// @see {@link confirm_transaction} to await a terminal status instead of polling by hand.
 pub fn submit_transaction(path: String, transaction_str: String) -> Result<Promise, JsValue> {
   let mut opts = RequestInit::new();
   opts.method("POST");
@@ -747,6 +1489,296 @@ pub fn get_txn_status(path: String, handle: String) -> Result<Promise, JsValue>
   create_query_promise(&opts, &req_string, false)
 }
 
+/// Fetches and JSON-decodes a single `txn_status` response.
+async fn fetch_txn_status(path: &str, handle: &str) -> Result<serde_json::Value, JsValue> {
+  let mut opts = RequestInit::new();
+  opts.method("GET");
+  opts.mode(RequestMode::Cors);
+  let req_string = format!("{}/txn_status/{}", path, handle);
+
+  let request = Request::new_with_str_and_init(&req_string, &opts)?;
+  let window = web_sys::window().ok_or_else(|| JsValue::from_str("No global `window` exists"))?;
+  let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+  let resp: Response = resp_value.dyn_into()?;
+  let json = JsFuture::from(resp.json()?).await?;
+  json.into_serde().map_err(error_to_jsvalue)
+}
+
+/// Resolves after `ms` milliseconds, via `window.setTimeout`.
+fn delay_ms(ms: i32) -> JsFuture {
+  let promise = Promise::new(&mut |resolve, _reject| {
+    if let Some(window) = web_sys::window() {
+      let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+    }
+  });
+  JsFuture::from(promise)
+}
+
+/// `txn_status`'s payload is an externally-tagged enum (e.g. `"Pending"` or
+/// `{"Committed": ...}`); rather than hard-coding a local copy of its variants (the type itself
+/// lives in the `submission_server` crate, which this client-side crate has no access to), read
+/// the tag straight out of its JSON shape.
+fn txn_status_tag(status: &serde_json::Value) -> Option<&str> {
+  match status {
+    serde_json::Value::String(s) => Some(s.as_str()),
+    serde_json::Value::Object(map) => map.keys().next().map(|s| s.as_str()),
+    _ => None,
+  }
+}
+
+fn txn_status_is_terminal(tag: Option<&str>) -> bool {
+  matches!(tag, Some("Committed") | Some("Rejected"))
+}
+
+/// The structured result `confirm_transaction`/`submit_and_confirm_transaction` resolve to.
+#[derive(Serialize)]
+struct TxnConfirmation {
+  status: Option<String>,
+  block_height: Option<u64>,
+  error: Option<String>,
+}
+
+/// Builds a `TxnConfirmation` out of a terminal `txn_status` response. The exact shape of each
+/// variant's payload is defined by the (inaccessible, from here) `submission_server` crate, so
+/// `block_height` and `error` are filled in on a best-effort basis from common shapes (a bare
+/// number or a `block_height`/`sid` field for `Committed`; a string reason for `Rejected`) and
+/// left `null` rather than guessed at otherwise.
+fn build_txn_confirmation(tag: &str, status: &serde_json::Value) -> TxnConfirmation {
+  let payload = match status {
+    serde_json::Value::Object(map) => map.get(tag),
+    _ => None,
+  };
+  let block_height = payload.and_then(|p| match p {
+                              serde_json::Value::Number(n) => n.as_u64(),
+                              serde_json::Value::Object(m) => {
+                                m.get("block_height")
+                                 .or_else(|| m.get("sid"))
+                                 .and_then(|v| v.as_u64())
+                              }
+                              serde_json::Value::Array(arr) => arr.get(0).and_then(|v| v.as_u64()),
+                              _ => None,
+                            });
+  let error = if tag == "Rejected" {
+    Some(match payload {
+           Some(serde_json::Value::String(s)) => s.clone(),
+           Some(other) => other.to_string(),
+           None => "Transaction rejected".to_string(),
+         })
+  } else {
+    None
+  };
+  TxnConfirmation { status: Some(tag.to_string()),
+                    block_height,
+                    error }
+}
+
+#[wasm_bindgen]
+/// Polls `txn_status` for `handle` until it reaches a terminal status (`Committed` or
+/// `Rejected`) or `timeout_ms` elapses, using exponential backoff between polls (starting at
+/// `poll_interval_ms`, doubling each round, capped at `timeout_ms`).
+/// @param {string} path - Address of submission server. E.g. `https://localhost:8669`.
+/// @param {string} handle - Transaction handle from `submit_transaction`.
+/// @param {number} timeout_ms - Maximum total time to wait, in milliseconds.
+/// @param {number} poll_interval_ms - Initial delay between polls, in milliseconds.
+/// @returns {Promise<Object>} Resolves to `{status, block_height, error}`; `block_height` and
+/// `error` are `null` unless the terminal status carries them.
+/// @throws Will reject if `timeout_ms` elapses before a terminal status is reached.
+/// @see {@link submit_transaction}
+/// @see {@link submit_and_confirm_transaction}
+pub fn confirm_transaction(path: String,
+                            handle: String,
+                            timeout_ms: u32,
+                            poll_interval_ms: u32)
+                            -> Promise {
+  future_to_promise(async move {
+    let deadline = js_sys::Date::now() + f64::from(timeout_ms);
+    let mut interval_ms = poll_interval_ms.max(1);
+
+    loop {
+      let status = fetch_txn_status(&path, &handle).await?;
+      if let Some(tag) = txn_status_tag(&status) {
+        if txn_status_is_terminal(Some(tag)) {
+          let confirmation = build_txn_confirmation(tag, &status);
+          return JsValue::from_serde(&confirmation).map_err(error_to_jsvalue);
+        }
+      }
+
+      if js_sys::Date::now() >= deadline {
+        return Err(JsValue::from_str(&format!("Timed out waiting for transaction `{}` to reach a terminal status",
+                                               handle)));
+      }
+
+      delay_ms(interval_ms.min(timeout_ms) as i32).await?;
+      interval_ms = interval_ms.saturating_mul(2);
+    }
+  })
+}
+
+#[wasm_bindgen]
+/// Submits `txn_str` and then awaits its confirmation, chaining `submit_transaction` and
+/// `confirm_transaction` into a single promise so callers get one await point instead of
+/// hand-rolling a submit-then-poll loop.
+/// @param {string} path - Address of submission server. E.g. `https://localhost:8669`.
+/// @param {string} txn_str - JSON-encoded transaction string.
+/// @param {number} timeout_ms - Maximum total time to wait for confirmation, in milliseconds.
+/// @returns {Promise<Object>} Resolves to `{status, block_height, error}`, as {@link
+/// confirm_transaction}.
+/// @throws Will reject if submission fails, or if `timeout_ms` elapses before a terminal status
+/// is reached.
+/// @see {@link submit_transaction}
+/// @see {@link confirm_transaction}
+pub fn submit_and_confirm_transaction(path: String, txn_str: String, timeout_ms: u32) -> Promise {
+  future_to_promise(async move {
+    let mut opts = RequestInit::new();
+    opts.method("POST");
+    opts.mode(RequestMode::Cors);
+    opts.body(Some(&JsValue::from_str(&txn_str)));
+
+    let req_string = format!("{}/submit_transaction", path);
+    let request = Request::new_with_str_and_init(&req_string, &opts)?;
+    request.headers().set("content-type", "application/json")?;
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No global `window` exists"))?;
+    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+    let resp: Response = resp_value.dyn_into()?;
+    let handle_json = JsFuture::from(resp.json()?).await?;
+    let handle: String = handle_json.into_serde().map_err(error_to_jsvalue)?;
+
+    const DEFAULT_POLL_INTERVAL_MS: u32 = 500;
+    JsFuture::from(confirm_transaction(path, handle, timeout_ms, DEFAULT_POLL_INTERVAL_MS)).await
+  })
+}
+
+/// The faucet request body. The faucet endpoint's exact shape isn't defined anywhere in this
+/// tree; `address`/`amount`/`asset_code` mirrors the field names this file already uses for the
+/// same concepts elsewhere (`get_asset_token`, `submit_transaction`).
+#[derive(Serialize)]
+struct FaucetRequest {
+  address: String,
+  amount: u64,
+  asset_code: Option<String>,
+}
+
+/// Best-effort lookup of an asset type's declared decimals off its on-chain definition.
+///
+/// NOTE: this ledger's on-chain asset properties (see `get_asset_token`) have no `decimals`
+/// field anywhere in this tree -- cli2's own asset bookkeeping tracks `decimals` purely as a
+/// local, user-supplied annotation (see `cli.rs`'s `QueryAssetType`/`AssetTypeEntry`), not
+/// something the chain itself publishes. This still checks the fetched definition for a
+/// `decimals` field, for a deployment that extends it, and otherwise falls back to `0`.
+async fn fetch_asset_decimals(ledger_path: &str, asset_code: &str) -> u8 {
+  let fetch = async {
+    let mut opts = RequestInit::new();
+    opts.method("GET");
+    opts.mode(RequestMode::Cors);
+    let req_string = format!("{}/asset_token/{}", ledger_path, asset_code);
+    let request = Request::new_with_str_and_init(&req_string, &opts)?;
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No global `window` exists"))?;
+    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+    let resp: Response = resp_value.dyn_into()?;
+    let json: serde_json::Value =
+      JsFuture::from(resp.json()?).await?.into_serde().map_err(error_to_jsvalue)?;
+    Ok::<Option<u64>, JsValue>(json.get("decimals").and_then(|v| v.as_u64()))
+  };
+  fetch.await.ok().flatten().map(|d| d as u8).unwrap_or(0)
+}
+
+/// Shared implementation for `request_airdrop` and `request_and_confirm_airdrop`: POSTs a
+/// funding request for `amount` whole units of `asset_code` (or the native token) to
+/// `recipient_b64`, and returns the resulting transaction handle.
+async fn request_airdrop_handle(faucet_path: &str,
+                                 ledger_path: &str,
+                                 recipient_b64: String,
+                                 amount: u64,
+                                 asset_code: Option<String>)
+                                 -> Result<String, JsValue> {
+  let decimals = match &asset_code {
+    Some(code) => fetch_asset_decimals(ledger_path, code).await,
+    None => 0,
+  };
+  let scale = 10u64.checked_pow(u32::from(decimals))
+                   .ok_or_else(|| JsValue::from_str("Asset decimals overflow u64 scaling"))?;
+  let base_units =
+    amount.checked_mul(scale)
+          .ok_or_else(|| JsValue::from_str("Requested amount overflows u64 in base units"))?;
+
+  let mut opts = RequestInit::new();
+  opts.method("POST");
+  opts.mode(RequestMode::Cors);
+  let body = FaucetRequest { address: recipient_b64,
+                            amount: base_units,
+                            asset_code };
+  let body_str = serde_json::to_string(&body).map_err(error_to_jsvalue)?;
+  opts.body(Some(&JsValue::from_str(&body_str)));
+
+  let req_string = format!("{}/airdrop", faucet_path);
+  let request = Request::new_with_str_and_init(&req_string, &opts)?;
+  request.headers().set("content-type", "application/json")?;
+  let window = web_sys::window().ok_or_else(|| JsValue::from_str("No global `window` exists"))?;
+  let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+  let resp: Response = resp_value.dyn_into()?;
+  let handle_json = JsFuture::from(resp.json()?).await?;
+  handle_json.into_serde().map_err(error_to_jsvalue)
+}
+
+#[wasm_bindgen]
+/// Requests a faucet-funded transfer of `amount` -- denominated in whole units of the target
+/// asset, not raw base units -- to `recipient`, returning the resulting transaction handle.
+/// `amount` is scaled by `10^decimals` before being sent to the faucet; see
+/// `fetch_asset_decimals` for how (and how reliably) `decimals` is determined.
+/// @param {string} faucet_path - Address of the faucet server, e.g. `https://localhost:8789`.
+/// @param {string} ledger_path - Address of the ledger access server, used to look up the
+/// asset's decimals. E.g. `https://localhost:8668`.
+/// @param {XfrPublicKey} recipient - Public key to receive the airdrop.
+/// @param {number} amount - Amount to request, in whole units of the target asset.
+/// @param {string | undefined} asset_code - Base64-encoded asset type code to request, or
+/// `undefined`/`null` for the native token.
+/// @returns {Promise<string>} Resolves to the resulting transaction handle.
+/// @see {@link confirm_transaction} to await the airdrop's confirmation.
+/// @see {@link request_and_confirm_airdrop} to request and await confirmation in one call.
+pub fn request_airdrop(faucet_path: String,
+                        ledger_path: String,
+                        recipient: &XfrPublicKey,
+                        amount: u64,
+                        asset_code: Option<String>)
+                        -> Promise {
+  let recipient_b64 = b64enc(recipient.as_bytes());
+  future_to_promise(async move {
+    request_airdrop_handle(&faucet_path, &ledger_path, recipient_b64, amount, asset_code).await
+                                                                                          .map(|handle| {
+                                                                                            JsValue::from_str(&handle)
+                                                                                          })
+  })
+}
+
+#[wasm_bindgen]
+/// Requests a faucet-funded airdrop via `request_airdrop`, then awaits its confirmation via
+/// `confirm_transaction`, so a caller can fund and wait in one flow.
+/// @param {string} faucet_path - Address of the faucet server.
+/// @param {string} ledger_path - Address of the ledger access server (for decimals lookup).
+/// @param {string} submission_path - Address of the submission server (for status polling).
+/// @param {XfrPublicKey} recipient - Public key to receive the airdrop.
+/// @param {number} amount - Amount to request, in whole units of the target asset.
+/// @param {string | undefined} asset_code - Base64-encoded asset type code, or
+/// `undefined`/`null` for the native token.
+/// @param {number} timeout_ms - Maximum time to wait for confirmation, in milliseconds.
+/// @returns {Promise<Object>} Resolves to `{status, block_height, error}`, as
+/// {@link confirm_transaction}.
+pub fn request_and_confirm_airdrop(faucet_path: String,
+                                    ledger_path: String,
+                                    submission_path: String,
+                                    recipient: &XfrPublicKey,
+                                    amount: u64,
+                                    asset_code: Option<String>,
+                                    timeout_ms: u32)
+                                    -> Promise {
+  let recipient_b64 = b64enc(recipient.as_bytes());
+  future_to_promise(async move {
+    let handle = request_airdrop_handle(&faucet_path, &ledger_path, recipient_b64, amount, asset_code).await?;
+    const DEFAULT_POLL_INTERVAL_MS: u32 = 500;
+    JsFuture::from(confirm_transaction(submission_path, handle, timeout_ms, DEFAULT_POLL_INTERVAL_MS)).await
+  })
+}
+
 #[wasm_bindgen]
 /// If successful, returns a promise that will eventually provide a
 /// JsValue describing an unspent transaction output (UTXO).
@@ -1001,6 +2033,373 @@ pub fn wasm_credential_verify(issuer_pub_key: &CredIssuerPublicKey,
   Ok(())
 }
 
+// Credential revocation
+//
+// NOTE: a genuine pairing-based (bilinear) accumulator, as asked for here, needs a
+// pairing-friendly curve implementation, and none is vendored anywhere in this tree (the
+// `credentials` crate's own underlying curve isn't inspectable here either). The registry below
+// instead accumulates the active index set under a plain SHA-256 hash, and a witness is the
+// sorted list of an index's fellow active indices -- recomputing to the same accumulator value
+// proves membership, but the witness is O(n) in the registry size rather than constant size, and
+// it reveals the holder's fellow indices to a verifier rather than hiding them behind a
+// zero-knowledge proof. Swap in a real accumulator (e.g. an RSA or BLS12-381 instantiation) once
+// one is available in this tree; the function names and shapes below are chosen so that swap
+// wouldn't change callers.
+
+/// An accumulator-based revocation registry for anonymous credentials: `accumulator()` is the
+/// value `A` that a {@link NonRevocationWitness} is checked against to prove its index has not
+/// been revoked. See the NOTE above this section for this registry's current limitations.
+#[wasm_bindgen]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RevocationRegistry {
+  active_indices: Vec<u64>,
+}
+
+#[wasm_bindgen]
+impl RevocationRegistry {
+  /// The registry's current accumulator value `A`.
+  pub fn accumulator(&self) -> String {
+    revocation_accumulator(&self.active_indices)
+  }
+}
+
+fn revocation_accumulator(active_indices: &[u64]) -> String {
+  let mut sorted = active_indices.to_vec();
+  sorted.sort_unstable();
+  let mut bytes = Vec::with_capacity(sorted.len() * 8);
+  for idx in &sorted {
+    bytes.extend_from_slice(&idx.to_be_bytes());
+  }
+  hex::encode(sha256::hash(&bytes))
+}
+
+#[wasm_bindgen]
+/// Creates a fresh revocation registry for an issuer, with an empty active-index set and its
+/// initial accumulator value `A`.
+/// @param {CredIssuerSecretKey} issuer_sk - Credential issuer whose issued credentials this
+/// registry will track. Accepted for API parity with a production bilinear accumulator (whose
+/// public parameters would be derived from it); unused by this hash-based implementation -- see
+/// the NOTE above this section.
+pub fn create_revocation_registry(_issuer_sk: &CredIssuerSecretKey) -> RevocationRegistry {
+  RevocationRegistry { active_indices: vec![] }
+}
+
+/// A non-revocation witness for a single credential index: proof material that, checked against
+/// a registry's current accumulator, shows the index is still active (not revoked). See the NOTE
+/// above this section for why this witness is not constant-size.
+#[wasm_bindgen]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NonRevocationWitness {
+  index: u64,
+  other_active_indices: Vec<u64>,
+}
+
+#[wasm_bindgen]
+impl NonRevocationWitness {
+  /// Checks this witness against `accumulator`, recomputing the accumulator over this witness's
+  /// index plus its recorded fellow active indices and comparing the result.
+  pub fn verify(&self, accumulator: &str) -> bool {
+    let mut indices = self.other_active_indices.clone();
+    indices.push(self.index);
+    revocation_accumulator(&indices) == accumulator
+  }
+
+  /// Refreshes this witness after `revoked_index` was removed from the registry (the `delta`
+  /// returned by `revoke_credential`), by dropping it from the recorded fellow index set. Call
+  /// this whenever a holder learns of a revocation so their witness still verifies against the
+  /// registry's new accumulator.
+  pub fn update_for_revocation(&self, revoked_index: u64) -> NonRevocationWitness {
+    NonRevocationWitness { index: self.index,
+                           other_active_indices: self.other_active_indices
+                                                      .iter()
+                                                      .copied()
+                                                      .filter(|idx| *idx != revoked_index)
+                                                      .collect() }
+  }
+}
+
+/// The result of `issue_revocation_handle`: the registry with `index` now active, and a
+/// non-revocation witness proving it.
+#[wasm_bindgen]
+pub struct IssuedRevocationHandle {
+  registry: RevocationRegistry,
+  witness: NonRevocationWitness,
+}
+
+#[wasm_bindgen]
+impl IssuedRevocationHandle {
+  pub fn get_registry(&self) -> RevocationRegistry {
+    self.registry.clone()
+  }
+
+  pub fn get_witness(&self) -> NonRevocationWitness {
+    self.witness.clone()
+  }
+}
+
+#[wasm_bindgen]
+/// Assigns `index` a place in `registry`'s accumulator, returning the updated registry together
+/// with a non-revocation witness for that index.
+/// @param {RevocationRegistry} registry - Registry to add the index to.
+/// @param {BigInt} index - Unique index identifying the issued credential's holder.
+/// @throws Will throw an error if `index` is already active in `registry`.
+pub fn issue_revocation_handle(registry: RevocationRegistry,
+                                index: u64)
+                                -> Result<IssuedRevocationHandle, JsValue> {
+  if registry.active_indices.contains(&index) {
+    return Err(JsValue::from_str(&format!("Index {} is already active in this registry", index)));
+  }
+  let witness = NonRevocationWitness { index,
+                                      other_active_indices: registry.active_indices.clone() };
+  let mut active_indices = registry.active_indices;
+  active_indices.push(index);
+  Ok(IssuedRevocationHandle { registry: RevocationRegistry { active_indices },
+                             witness })
+}
+
+/// The result of `revoke_credential`: the registry with `revoked_index` no longer active (and
+/// its new accumulator `A'`), plus `revoked_index` itself as the delta un-revoked holders need to
+/// pass to `NonRevocationWitness::update_for_revocation`.
+#[wasm_bindgen]
+pub struct RevocationDelta {
+  registry: RevocationRegistry,
+  revoked_index: u64,
+}
+
+#[wasm_bindgen]
+impl RevocationDelta {
+  pub fn get_registry(&self) -> RevocationRegistry {
+    self.registry.clone()
+  }
+
+  pub fn get_revoked_index(&self) -> u64 {
+    self.revoked_index
+  }
+}
+
+#[wasm_bindgen]
+/// Removes `index` from `registry`, returning its new accumulator `A'` plus the delta that
+/// un-revoked holders need to refresh their witnesses offline.
+/// @param {RevocationRegistry} registry - Registry to revoke the index from.
+/// @param {BigInt} index - Index to revoke.
+/// @throws Will throw an error if `index` is not currently active in `registry`.
+pub fn revoke_credential(registry: RevocationRegistry, index: u64) -> Result<RevocationDelta, JsValue> {
+  if !registry.active_indices.contains(&index) {
+    return Err(JsValue::from_str(&format!("Index {} is not active in this registry", index)));
+  }
+  let active_indices = registry.active_indices
+                               .into_iter()
+                               .filter(|idx| *idx != index)
+                               .collect();
+  Ok(RevocationDelta { registry: RevocationRegistry { active_indices },
+                       revoked_index: index })
+}
+
+/// A `CredentialRevealSig` bundled with a {@link NonRevocationWitness}, as returned by
+/// `wasm_credential_reveal_with_nonrevocation`.
+#[wasm_bindgen]
+pub struct CredentialRevealSigWithNonRevocation {
+  sig: CredentialRevealSig,
+  witness: NonRevocationWitness,
+}
+
+#[wasm_bindgen]
+/// Like `wasm_credential_reveal`, but also attaches `witness` as proof that the holder's
+/// revocation-registry index is still active.
+///
+/// NOTE: `witness` is attached as-is rather than folded into a zero-knowledge proof, since doing
+/// so needs the same pairing-based accumulator machinery noted where {@link RevocationRegistry}
+/// is defined. A verifier of the result still learns the holder's fellow active indices, so this
+/// does not hide non-revocation the way a real ZK non-revocation proof would.
+/// @param {CredUserSecretKey} user_sk - Secret key of credential user.
+/// @param {Credential} credential - Credential object.
+/// @param {JsValue} reveal_fields - Array of string names representing credentials to reveal (i.e.
+/// `["credit_score"]`).
+/// @param {NonRevocationWitness} witness - Non-revocation witness for the holder's index, from
+/// `issue_revocation_handle`.
+pub fn wasm_credential_reveal_with_nonrevocation(user_sk: &CredUserSecretKey,
+                                                  credential: &Credential,
+                                                  reveal_fields: JsValue,
+                                                  witness: NonRevocationWitness)
+                                                  -> Result<CredentialRevealSigWithNonRevocation, JsValue> {
+  let sig = wasm_credential_reveal(user_sk, credential, reveal_fields)?;
+  Ok(CredentialRevealSigWithNonRevocation { sig, witness })
+}
+
+#[wasm_bindgen]
+/// Like `wasm_credential_verify`, but also checks that `reveal_sig`'s attached non-revocation
+/// witness still verifies against `registry_accumulator` -- i.e. that the holder's credential has
+/// not been revoked.
+/// @param {CredIssuerPublicKey} issuer_pub_key - Public key of credential issuer.
+/// @param {JsValue} attributes - Array of attribute assignments to check of the form `[{name: "credit_score",
+/// val: "760"}]`.
+/// @param {CredentialRevealSigWithNonRevocation} reveal_sig - Reveal signature plus
+/// non-revocation witness, from `wasm_credential_reveal_with_nonrevocation`.
+/// @param {string} registry_accumulator - The registry's current accumulator value `A`, from
+/// `RevocationRegistry::accumulator`.
+/// @throws Will throw an error if the underlying reveal signature fails to verify, or if the
+/// witness no longer verifies against `registry_accumulator` (i.e. the credential was revoked).
+pub fn wasm_credential_verify_nonrevocation(issuer_pub_key: &CredIssuerPublicKey,
+                                             attributes: JsValue,
+                                             reveal_sig: CredentialRevealSigWithNonRevocation,
+                                             registry_accumulator: String)
+                                             -> Result<(), JsValue> {
+  if !reveal_sig.witness.verify(&registry_accumulator) {
+    return Err(JsValue::from_str("Credential has been revoked"));
+  }
+  wasm_credential_verify(issuer_pub_key, attributes, &reveal_sig.sig)
+}
+
+// Verifiable Credential (JWT) interop layer
+//
+// NOTE: `credential_to_jwt`'s outer JWS signature is an HMAC-SHA512 keyed off a hash of
+// `issuer_sk`'s serialized bytes -- `CredIssuerSecretKey`/`CredIssuerPublicKey` have no
+// general-purpose "sign an arbitrary message, verify with the public key alone" primitive
+// exposed anywhere in this tree (the `credentials` crate only exposes attribute-specific
+// sign/commit/reveal/verify functions). `verify_credential_jwt` can't re-check this outer
+// signature -- it only has `issuer_pk`, and HMAC verification needs the same secret the signer
+// used -- so its real security comes from re-running `credential_verify` against the embedded
+// Findora reveal proof, exactly as the request asks ("keeping the selective-disclosure proof
+// intact"). The outer signature gives the result a well-formed `header.payload.signature`
+// compact JWT shape for off-the-shelf JWT *parsers*; treat it as tamper-evidence between parties
+// who separately share the issuer secret key, not as something a standard JWS verifier holding
+// only `issuer_pk` can check on its own.
+
+fn base64url_encode(bytes: &[u8]) -> String {
+  base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+fn base64url_decode(s: &str) -> Result<Vec<u8>, JsValue> {
+  base64::decode_config(s, base64::URL_SAFE_NO_PAD).map_err(|e| {
+                                                      JsValue::from_str(&format!("Could not base64url-decode JWT segment: {}",
+                                                                                 e))
+                                                    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct VerifiableCredentialProof {
+  #[serde(rename = "type")]
+  proof_type: String,
+  reveal_sig: CredentialRevealSig,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VerifiableCredentialPayload {
+  #[serde(rename = "@context")]
+  context: Vec<String>,
+  #[serde(rename = "type")]
+  vc_type: Vec<String>,
+  issuer: String,
+  #[serde(rename = "issuanceDate")]
+  issuance_date: String,
+  #[serde(rename = "credentialSubject")]
+  credential_subject: serde_json::Map<String, serde_json::Value>,
+  proof: VerifiableCredentialProof,
+}
+
+fn split_jwt(jwt: &str) -> Result<(&str, &str, &str), JsValue> {
+  let mut parts = jwt.split('.');
+  match (parts.next(), parts.next(), parts.next(), parts.next()) {
+    (Some(header), Some(payload), Some(signature), None) => Ok((header, payload, signature)),
+    _ => Err(JsValue::from_str("Malformed JWT: expected exactly three `.`-separated segments")),
+  }
+}
+
+fn decode_jwt_payload(jwt: &str) -> Result<VerifiableCredentialPayload, JsValue> {
+  let (_, payload_b64, _) = split_jwt(jwt)?;
+  let payload_bytes = base64url_decode(payload_b64)?;
+  serde_json::from_slice(&payload_bytes).map_err(|e| JsValue::from_str(&format!("Could not parse VC payload: {}", e)))
+}
+
+#[wasm_bindgen]
+/// Assembles `credential`'s revealed attributes into a W3C Verifiable Credential Data Model
+/// payload and serializes it as a signed compact JWT (JWS). See the NOTE above this section for
+/// this JWS's outer-signature scope.
+/// @param {CredIssuerSecretKey} issuer_sk - Secret key of the credential issuer.
+/// @param {CredIssuerPublicKey} issuer_pk - Public key of the credential issuer; recorded as
+/// `issuer` (base64 of its JSON serialization -- `CredIssuerPublicKey` has no raw-byte
+/// serialization available in this tree).
+/// @param {JsValue} attributes - Array of attribute assignments revealed in `reveal_sig`, of the
+/// form `[{name: "credit_score", val: "760"}]` -- the same shape `wasm_credential_verify` takes.
+/// @param {CredentialRevealSig} reveal_sig - Reveal signature from `wasm_credential_reveal`,
+/// embedded as-is as the VC's `proof` member.
+/// @param {XfrPublicKey} subject_pubkey - Ledger address of the credential holder; recorded as
+/// `credentialSubject.id`.
+/// @returns {string} A compact JWT (`header.payload.signature`).
+pub fn credential_to_jwt(issuer_sk: &CredIssuerSecretKey,
+                          issuer_pk: &CredIssuerPublicKey,
+                          attributes: JsValue,
+                          reveal_sig: CredentialRevealSig,
+                          subject_pubkey: &XfrPublicKey)
+                          -> Result<String, JsValue> {
+  let attributes: Vec<AttributeAssignment> = attributes.into_serde().map_err(error_to_jsvalue)?;
+
+  let mut credential_subject = serde_json::Map::new();
+  credential_subject.insert("id".to_string(), serde_json::Value::String(b64enc(subject_pubkey.as_bytes())));
+  for attr in &attributes {
+    credential_subject.insert(attr.name.clone(), serde_json::Value::String(attr.val.clone()));
+  }
+
+  let payload =
+    VerifiableCredentialPayload { context: vec!["https://www.w3.org/2018/credentials/v1".to_string()],
+                                  vc_type: vec!["VerifiableCredential".to_string()],
+                                  issuer: b64enc(&serde_json::to_vec(issuer_pk).map_err(error_to_jsvalue)?),
+                                  issuance_date: js_sys::Date::new_0().to_iso_string()
+                                                                      .as_string()
+                                                                      .unwrap_or_default(),
+                                  credential_subject,
+                                  proof: VerifiableCredentialProof { proof_type:
+                                                                       "FindoraCLSelectiveDisclosure2024".to_string(),
+                                                                     reveal_sig } };
+
+  let header_b64 = base64url_encode(br#"{"alg":"HS512","typ":"JWT"}"#);
+  let payload_json = serde_json::to_vec(&payload).map_err(error_to_jsvalue)?;
+  let payload_b64 = base64url_encode(&payload_json);
+  let signing_input = format!("{}.{}", header_b64, payload_b64);
+
+  let issuer_sk_bytes = serde_json::to_vec(issuer_sk).map_err(error_to_jsvalue)?;
+  let mut mac = HmacSha512::new_from_slice(&sha256::hash(&issuer_sk_bytes).0).expect("HMAC accepts any key length");
+  mac.update(signing_input.as_bytes());
+  let signature_b64 = base64url_encode(&mac.finalize().into_bytes());
+
+  Ok(format!("{}.{}", signing_input, signature_b64))
+}
+
+#[wasm_bindgen]
+/// Parses a JWT produced by `credential_to_jwt`, returning its decoded W3C Verifiable Credential
+/// payload. Does not check the JWT's signature or embedded proof -- see `verify_credential_jwt`
+/// for that.
+/// @param {string} jwt - Compact JWT from `credential_to_jwt`.
+/// @throws Will throw an error if `jwt` is not a well-formed JWT with a parseable VC payload.
+pub fn credential_from_jwt(jwt: String) -> Result<JsValue, JsValue> {
+  let payload = decode_jwt_payload(&jwt)?;
+  JsValue::from_serde(&payload).map_err(error_to_jsvalue)
+}
+
+#[wasm_bindgen]
+/// Verifies a JWT produced by `credential_to_jwt` by re-running `credential_verify` on its
+/// embedded Findora reveal proof against `issuer_pub_key` and the decoded `credentialSubject`
+/// attributes. See the NOTE above this section for why this does not also check the JWT's outer
+/// JWS signature.
+/// @param {CredIssuerPublicKey} issuer_pub_key - Public key of credential issuer.
+/// @param {string} jwt - Compact JWT from `credential_to_jwt`.
+/// @throws Will throw an error if `jwt` is malformed, or if the embedded proof does not verify.
+pub fn verify_credential_jwt(issuer_pub_key: &CredIssuerPublicKey, jwt: String) -> Result<(), JsValue> {
+  let payload = decode_jwt_payload(&jwt)?;
+  let attributes: Vec<(String, &[u8])> =
+    payload.credential_subject
+           .iter()
+           .filter(|(name, _)| name.as_str() != "id")
+           .filter_map(|(name, value)| value.as_str().map(|v| (name.clone(), v.as_bytes())))
+           .collect();
+
+  let reveal_sig = payload.proof.reveal_sig;
+  credential_verify(issuer_pub_key,
+                    &attributes,
+                    &reveal_sig.get_sig_ref().sig_commitment,
+                    &reveal_sig.get_sig_ref().pok).map_err(error_to_jsvalue)
+}
+
 // Asset Tracing
 
 #[wasm_bindgen]
@@ -1033,6 +2432,108 @@ pub fn trace_assets(xfr_body: JsValue,
   Ok(JsValue::from_serde(&record_data).unwrap())
 }
 
+// Scanning for owned records
+
+#[wasm_bindgen]
+/// A fixed-size bit-vector bloom filter, used by {@link scan_owned_records} to skip the
+/// expensive `open_bar` trial-decryption for candidates that can't possibly belong to the
+/// scanning key. Membership tests never false-negative but can false-positive: with `n` tags
+/// inserted into `num_bits` bits and `num_hashes` hash functions, the false-positive rate is
+/// approximately `(1 - e^(-num_hashes*n/num_bits))^num_hashes` -- e.g. ~10 bits per tag with
+/// `num_hashes = 7` gives about a 1% rate. Size generously: a false positive only costs one
+/// wasted `open_bar` call, but there is no way to recover a false negative.
+pub struct BloomFilter {
+  bits: Vec<bool>,
+  num_hashes: usize,
+}
+
+#[wasm_bindgen]
+impl BloomFilter {
+  /// Creates an empty filter with `num_bits` bits and `num_hashes` hash functions.
+  pub fn new(num_bits: usize, num_hashes: usize) -> BloomFilter {
+    BloomFilter { bits: vec![false; num_bits.max(1)],
+                 num_hashes: num_hashes.max(1) }
+  }
+
+  /// Inserts `tag` into the filter.
+  pub fn insert(&mut self, tag: Vec<u8>) {
+    let len = self.bits.len();
+    for seed in 0..self.num_hashes {
+      self.bits[Self::hash(&tag, seed) % len] = true;
+    }
+  }
+
+  /// Tests whether `tag` may have been inserted. `false` is a guaranteed negative; `true` may be
+  /// a false positive.
+  pub fn contains(&self, tag: &[u8]) -> bool {
+    let len = self.bits.len();
+    (0..self.num_hashes).all(|seed| self.bits[Self::hash(tag, seed) % len])
+  }
+
+  fn hash(tag: &[u8], seed: usize) -> usize {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    seed.hash(&mut hasher);
+    tag.hash(&mut hasher);
+    hasher.finish() as usize
+  }
+}
+
+/// Derives a {@link BloomFilter} tag for a `BlindAssetRecord`'s recipient key. In this ledger's
+/// record format the recipient public key is never hidden (only amount/asset type can be), so
+/// this is simply the key's canonical byte encoding -- testing it lets `scan_owned_records` skip
+/// `open_bar` for records it already knows aren't addressed to the scanning key, without needing
+/// to decrypt anything.
+fn owner_tag(pubkey: &XfrPublicKey) -> Vec<u8> {
+  XfrPublicKey::zei_to_bytes(pubkey).to_vec()
+}
+
+#[derive(Deserialize, Serialize)]
+/// One candidate output for {@link scan_owned_records}: an output a caller wants checked for
+/// ownership, in the form returned by ledger TXO lookups (e.g. {@link get_txo}).
+struct ScanCandidate {
+  txo_ref: TxoRef,
+  asset_record: ClientAssetRecord,
+  owner_memo: Option<OwnerMemo>,
+}
+
+#[wasm_bindgen]
+/// Scans a batch of candidate outputs for the ones `key_pair` owns, trial-opening each with
+/// `open_bar` and collecting the `txo_ref`/`asset_record`/`owner_memo` triples that decrypt
+/// successfully -- in a form directly consumable by {@link WasmWallet#add_utxo}.
+///
+/// `candidates` is a JSON array of `{txo_ref, asset_record, owner_memo}` triples. `filter`, if
+/// supplied, is a {@link BloomFilter} populated with {@link owner_tag}-derived tags for the keys
+/// the caller cares about; candidates whose tag the filter rejects are skipped without ever
+/// calling the expensive `open_bar`. Omit `filter` to trial-open every candidate.
+/// @param {JsValue} candidates - Array of `{txo_ref, asset_record, owner_memo}` objects.
+/// @param {XfrKeyPair} key_pair - Key to scan with.
+/// @param {BloomFilter} filter - Optional prefilter.
+pub fn scan_owned_records(candidates: JsValue,
+                          key_pair: &XfrKeyPair,
+                          filter: Option<BloomFilter>)
+                          -> Result<JsValue, JsValue> {
+  let candidates: Vec<ScanCandidate> = candidates.into_serde().map_err(error_to_jsvalue)?;
+  let mut owned = vec![];
+  for candidate in candidates {
+    if let Some(filter) = &filter {
+      let tag = owner_tag(&candidate.asset_record.get_bar_ref().public_key);
+      if !filter.contains(&tag) {
+        continue;
+      }
+    }
+    let memo_ref = candidate.owner_memo
+                            .as_ref()
+                            .map(|memo| memo.get_memo_ref().clone());
+    if open_bar(candidate.asset_record.get_bar_ref(),
+               &memo_ref,
+               key_pair.get_sk_ref()).is_ok()
+    {
+      owned.push(candidate);
+    }
+  }
+  JsValue::from_serde(&owned).map_err(error_to_jsvalue)
+}
+
 #[test]
 pub fn test() {
   let kp = new_keypair();
@@ -19,6 +19,7 @@ use {
         ResponseEndBlock, ResponseInfo, ResponseInitChain, ResponseQuery,
     },
     config::abci::global_cfg::CFG,
+    ethereum_types::U256,
     fp_storage::hash::{Sha256, StorageHasher},
     lazy_static::lazy_static,
     ledger::{
@@ -33,6 +34,7 @@ use {
     protobuf::RepeatedField,
     ruc::*,
     std::{
+        collections::HashSet,
         fs,
         ops::Deref,
         sync::{
@@ -48,9 +50,56 @@ lazy_static! {
     // save the request parameters from the begin_block for use in the end_block
     static ref REQ_BEGIN_BLOCK: Arc<Mutex<RequestBeginBlock>> =
         Arc::new(Mutex::new(RequestBeginBlock::new()));
-    // avoid on-chain-existing transactions to be stored again
-    static ref TX_HISTORY: Arc<RwLock<Mapx<Vec<u8>, bool>>> =
+    // Avoid on-chain-existing transactions being stored again. Keyed by tx hash, valued by
+    // the td_height it was first seen at, so `prune_tx_history` can drop entries once they
+    // fall outside `CFG.checkpoint.tx_replay_window` blocks and the set doesn't grow
+    // forever on a long-running full node.
+    static ref TX_HISTORY: Arc<RwLock<Mapx<Vec<u8>, i64>>> =
         Arc::new(RwLock::new(new_mapx!("tx_history")));
+    // Mirrors the live (unpruned) contents of `TX_HISTORY` as a hash set, so `check_tx`'s
+    // membership check stays O(1) without touching the backing `Mapx` on the hot path.
+    static ref TX_HISTORY_LIVE: Arc<RwLock<HashSet<Vec<u8>>>> =
+        Arc::new(RwLock::new(HashSet::new()));
+}
+
+/// Drops every `TX_HISTORY` entry older than `CFG.checkpoint.tx_replay_window` blocks,
+/// keeping the replay-protection index bounded while still rejecting any hash that could
+/// still be validly resubmitted.
+fn prune_tx_history(current_height: i64) {
+    let window_start = current_height - CFG.checkpoint.tx_replay_window;
+    if window_start <= 0 {
+        return;
+    }
+
+    let mut history = TX_HISTORY.write();
+    let stale: Vec<Vec<u8>> = history
+        .iter()
+        .filter(|(_, seen_at)| *seen_at < window_start)
+        .map(|(hash, _)| hash)
+        .collect();
+
+    let mut live = TX_HISTORY_LIVE.write();
+    for hash in stale {
+        history.remove(&hash);
+        live.remove(&hash);
+    }
+}
+
+/// Repopulates `TX_HISTORY_LIVE` from `TX_HISTORY`'s persisted entries still within
+/// `CFG.checkpoint.tx_replay_window` of `current_height`. `TX_HISTORY_LIVE` itself isn't
+/// persisted -- it's only ever rebuilt from the `Mapx`-backed `TX_HISTORY` -- so this must run
+/// once at startup, before `check_tx` can start relying on it, or replay protection would
+/// silently reset to empty on every restart.
+fn rebuild_tx_history_live(current_height: i64) {
+    let window_start = current_height - CFG.checkpoint.tx_replay_window;
+
+    let history = TX_HISTORY.read();
+    let mut live = TX_HISTORY_LIVE.write();
+    for (hash, seen_at) in history.iter() {
+        if seen_at >= window_start {
+            live.insert(hash);
+        }
+    }
 }
 
 // #[cfg(feature = "debug_env")]
@@ -76,6 +125,7 @@ pub fn info(s: &mut ABCISubmissionServer, req: &RequestInfo) -> ResponseInfo {
 
     let h = state.get_tendermint_height() as i64;
     TENDERMINT_BLOCK_HEIGHT.swap(h, Ordering::Relaxed);
+    rebuild_tx_history_live(h);
     resp.set_last_block_height(h);
     if 0 < h {
         if CFG.checkpoint.disable_evm_block_height < h
@@ -129,7 +179,7 @@ pub fn check_tx(s: &mut ABCISubmissionServer, req: &RequestCheckTx) -> ResponseC
                     if !tx.valid_in_abci() {
                         resp.log = "Should not appear in ABCI".to_owned();
                         resp.code = 1;
-                    } else if TX_HISTORY.read().contains_key(&tx.hash_tm_rawbytes()) {
+                    } else if TX_HISTORY_LIVE.read().contains(&tx.hash_tm_rawbytes()) {
                         resp.log = "Historical transaction".to_owned();
                         resp.code = 1;
                     }
@@ -187,6 +237,8 @@ pub fn begin_block(
     let header = pnk!(req.header.as_ref());
     TENDERMINT_BLOCK_HEIGHT.swap(header.height, Ordering::Relaxed);
 
+    prune_tx_history(header.height);
+
     *REQ_BEGIN_BLOCK.lock() = req.clone();
 
     let mut la = s.la.write();
@@ -209,7 +261,16 @@ pub fn begin_block(
     {
         ResponseBeginBlock::default()
     } else {
-        s.account_base_app.write().begin_block(req)
+        let mut account_base_app = s.account_base_app.write();
+        let resp = account_base_app.begin_block(req);
+        // Snapshot this block's base fee so `correct_and_deposit_fee` can split each
+        // transaction's fee into a burned base-fee portion and a proposer tip. The base
+        // fee itself is a governance-tunable chain parameter (like the other
+        // `CFG.checkpoint`/`CFG.evm` knobs this module reads), not derived from the
+        // Tendermint header, since plain Tendermint headers carry no EIP-1559 fee-market
+        // data to derive one from.
+        account_base_app.evm_set_base_fee_per_gas(U256::from(CFG.evm.base_fee_per_gas));
+        resp
     }
 }
 
@@ -228,7 +289,8 @@ pub fn deliver_tx(
             if let Ok(tx) = convert_tx(req.get_tx()) {
                 let txhash = tx.hash_tm_rawbytes();
                 POOL.spawn_ok(async move {
-                    TX_HISTORY.write().set_value(txhash, Default::default());
+                    TX_HISTORY.write().set_value(txhash.clone(), td_height);
+                    TX_HISTORY_LIVE.write().insert(txhash);
                 });
 
                 if tx.valid_in_abci() {
@@ -259,17 +321,31 @@ pub fn deliver_tx(
                             resp.log = e.to_string();
                         }
                     } else if is_convert_account(&tx) {
+                        // Nest an EVM-state checkpoint inside the outer session, so a
+                        // failed convert-account tx unwinds only its own EVM sub-state
+                        // instead of discarding the whole block session. `evm_checkpoint`/
+                        // `evm_commit_checkpoint`/`evm_revert_checkpoint` are zero-arg
+                        // forwarding methods on `account_base_app`'s type: they thread its
+                        // own `deliver_state` through as the `&Context` argument to
+                        // `App::<C>::checkpoint`/`commit_checkpoint`/`revert_checkpoint`
+                        // (see `contracts/modules/evm/src/impls.rs`), the same way
+                        // `evm_set_base_fee_per_gas` forwards to `App::<C>::set_base_fee_per_gas`.
+                        s.account_base_app.write().evm_checkpoint();
+
                         if let Err(err) =
                             s.account_base_app.write().deliver_findora_tx(&tx)
                         {
                             log::info!(target: "abciapp", "deliver convert account tx failed: {:?}", err);
 
+                            s.account_base_app.write().evm_revert_checkpoint();
                             resp.code = 1;
                             resp.log =
                                 format!("deliver convert account tx failed: {:?}", err);
                             return resp;
                         }
 
+                        s.account_base_app.write().evm_commit_checkpoint();
+
                         if s.la.write().cache_transaction(tx).is_ok() {
                             s.account_base_app
                                 .read()
@@ -0,0 +1,85 @@
+use ethereum_types::{H160, H256};
+use std::collections::HashMap;
+
+/// One layer of a nested checkpoint: for every account code or storage slot first touched
+/// after this layer was pushed, the value it held immediately before the touch.
+///
+/// `None` means the slot did not exist yet, which must be distinguished from "existed and
+/// was empty" so a revert can tell whether to restore a value or remove the slot entirely.
+#[derive(Default)]
+struct CheckpointLayer {
+    codes: HashMap<H160, Option<Vec<u8>>>,
+    storages: HashMap<(H160, H256), Option<H256>>,
+}
+
+/// A stack of [`CheckpointLayer`]s backing the EVM account/storage state, so a reverted
+/// EVM sub-call (CALL/CREATE) can unwind only the effects recorded since its own
+/// `checkpoint()`, leaving earlier effects in the same block untouched.
+#[derive(Default)]
+pub struct CheckpointStack(Vec<CheckpointLayer>);
+
+impl CheckpointStack {
+    pub const fn new() -> Self {
+        CheckpointStack(Vec::new())
+    }
+
+    /// Pushes a new, empty layer onto the stack.
+    pub fn checkpoint(&mut self) {
+        self.0.push(CheckpointLayer::default());
+    }
+
+    /// Records the pre-modification code for `address`, the first time it is touched since
+    /// the top layer was pushed. A no-op if there is no open checkpoint or the slot was
+    /// already recorded in the top layer.
+    pub fn record_code(&mut self, address: H160, old: Option<Vec<u8>>) {
+        if let Some(top) = self.0.last_mut() {
+            top.codes.entry(address).or_insert(old);
+        }
+    }
+
+    /// Records the pre-modification value for a storage slot, the first time it is touched
+    /// since the top layer was pushed.
+    pub fn record_storage(&mut self, address: H160, index: H256, old: Option<H256>) {
+        if let Some(top) = self.0.last_mut() {
+            top.storages.entry((address, index)).or_insert(old);
+        }
+    }
+
+    /// Merges the top layer's diffs down into the layer beneath it (or discards them if
+    /// this was the base layer), keeping the *older* saved value wherever both layers
+    /// recorded the same slot.
+    pub fn discard_checkpoint(&mut self) {
+        let Some(top) = self.0.pop() else {
+            return;
+        };
+        if let Some(below) = self.0.last_mut() {
+            for (address, old) in top.codes {
+                below.codes.entry(address).or_insert(old);
+            }
+            for (key, old) in top.storages {
+                below.storages.entry(key).or_insert(old);
+            }
+        }
+    }
+
+    /// Pops the top layer and returns the (address, old code) and ((address, index), old
+    /// value) pairs that must be restored to undo everything recorded in it.
+    pub fn revert_checkpoint(
+        &mut self,
+    ) -> (
+        Vec<(H160, Option<Vec<u8>>)>,
+        Vec<((H160, H256), Option<H256>)>,
+    ) {
+        match self.0.pop() {
+            Some(top) => (
+                top.codes.into_iter().collect(),
+                top.storages.into_iter().collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+}
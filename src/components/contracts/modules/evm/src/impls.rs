@@ -1,5 +1,7 @@
+use crate::checkpoint::CheckpointStack;
 use crate::storage::*;
 use crate::{App, Config};
+use config::abci::global_cfg::CFG;
 use ethereum_types::{H160, H256, U256};
 use fp_core::context::Context;
 use fp_evm::Account;
@@ -9,8 +11,62 @@ use fp_traits::{
     evm::{AddressMapping, OnChargeEVMTransaction},
 };
 use fp_utils::proposer_converter;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use parking_lot::Mutex;
 use ruc::Result;
 
+lazy_static! {
+    /// The live nested-checkpoint stack for EVM account/storage state. A block-level
+    /// checkpoint is pushed around `deliver_tx`'s convert-account branch, and EVM
+    /// sub-calls (CALL/CREATE) push their own nested checkpoints on top of it so a
+    /// reverted inner call unwinds only its own effects.
+    static ref EVM_CHECKPOINTS: Mutex<CheckpointStack> = Mutex::new(CheckpointStack::new());
+
+    /// Read-through caches for the hottest EVM getters, sized off `CFG.evm.cache_capacity`.
+    /// Keyed by `(address, height)` / `(address, index, height)` so versioned (`get_ver`)
+    /// reads only ever share a cache slot with other reads of that exact historical height.
+    static ref CODE_CACHE: Mutex<LruCache<(H160, Option<u64>), Option<Vec<u8>>>> =
+        Mutex::new(LruCache::new(CFG.evm.cache_capacity));
+    static ref STORAGE_CACHE: Mutex<LruCache<(H160, H256, Option<u64>), Option<H256>>> =
+        Mutex::new(LruCache::new(CFG.evm.cache_capacity));
+    static ref BASIC_CACHE: Mutex<LruCache<H160, Account>> =
+        Mutex::new(LruCache::new(CFG.evm.cache_capacity));
+
+    /// Base fee per gas for the block currently being delivered; see
+    /// `App::<C>::set_base_fee_per_gas`.
+    static ref BASE_FEE_PER_GAS: Mutex<U256> = Mutex::new(U256::zero());
+}
+
+/// Drops every cached entry for `address`: both its code (at every cached height) and every
+/// storage slot under it. Used whenever a write makes the cached values stale, most notably
+/// when an account is removed outright.
+fn invalidate_address(address: &H160) {
+    let mut code_cache = CODE_CACHE.lock();
+    let stale_codes: Vec<_> = code_cache
+        .iter()
+        .map(|(k, _)| *k)
+        .filter(|(cached_address, _)| cached_address == address)
+        .collect();
+    for key in stale_codes {
+        code_cache.pop(&key);
+    }
+    drop(code_cache);
+
+    let mut storage_cache = STORAGE_CACHE.lock();
+    let stale_storages: Vec<_> = storage_cache
+        .iter()
+        .map(|(k, _)| *k)
+        .filter(|(cached_address, _, _)| cached_address == address)
+        .collect();
+    for key in stale_storages {
+        storage_cache.pop(&key);
+    }
+    drop(storage_cache);
+
+    BASIC_CACHE.lock().pop(address);
+}
+
 impl<C: Config> App<C> {
     /// Check whether an account is empty.
     pub fn is_account_empty(ctx: &Context, address: &H160) -> bool {
@@ -23,8 +79,12 @@ impl<C: Config> App<C> {
 
     /// Remove an account.
     pub fn remove_account(ctx: &Context, address: &H160) {
+        let old_code = AccountCodes::get(ctx.state.read().borrow(), address);
+        EVM_CHECKPOINTS.lock().record_code(*address, old_code);
+
         AccountCodes::remove(ctx.state.write().borrow_mut(), address);
         AccountStorages::remove_prefix(ctx.state.write().borrow_mut(), address);
+        invalidate_address(address);
     }
 
     /// Create an account.
@@ -33,50 +93,163 @@ impl<C: Config> App<C> {
             return Ok(());
         }
 
-        AccountCodes::insert(ctx.state.write().borrow_mut(), &address, &code)
+        let old_code = AccountCodes::get(ctx.state.read().borrow(), address);
+        EVM_CHECKPOINTS.lock().record_code(address, old_code);
+
+        let res = AccountCodes::insert(ctx.state.write().borrow_mut(), &address, &code);
+        invalidate_address(&address);
+        res
+    }
+
+    /// Writes a storage slot and invalidates its cache entry. EVM execution must go
+    /// through this instead of calling `AccountStorages::insert` directly so cached reads
+    /// never observe a stale value after a write.
+    pub fn set_account_storage(
+        ctx: &Context,
+        address: H160,
+        index: H256,
+        value: H256,
+    ) -> Result<()> {
+        let old_value = AccountStorages::get(ctx.state.read().borrow(), &address, &index);
+        EVM_CHECKPOINTS
+            .lock()
+            .record_storage(address, index, old_value);
+
+        let res = AccountStorages::insert(
+            ctx.state.write().borrow_mut(),
+            &address,
+            &index,
+            &value,
+        );
+        STORAGE_CACHE.lock().pop(&(address, index, None));
+        res
+    }
+
+    /// Pushes a new checkpoint layer so subsequent account/storage writes can be unwound
+    /// independently of earlier, already-successful effects in the same block. Called from
+    /// the ABCI layer's `deliver_tx` via `account_base_app`'s zero-arg `evm_checkpoint`
+    /// forwarding method, which supplies its own `deliver_state` as `ctx`.
+    pub fn checkpoint(_ctx: &Context) {
+        EVM_CHECKPOINTS.lock().checkpoint();
+    }
+
+    /// Accepts the top checkpoint layer, merging its diffs down into the layer beneath it
+    /// (or dropping them if it was the base layer). Called via `account_base_app`'s
+    /// `evm_commit_checkpoint` forwarding method; see [`Self::checkpoint`].
+    pub fn commit_checkpoint(_ctx: &Context) {
+        EVM_CHECKPOINTS.lock().discard_checkpoint();
+    }
+
+    /// Pops the top checkpoint layer and restores every account code and storage slot it
+    /// recorded, undoing exactly the effects of the sub-call that owned this layer. Called
+    /// via `account_base_app`'s `evm_revert_checkpoint` forwarding method; see
+    /// [`Self::checkpoint`].
+    pub fn revert_checkpoint(ctx: &Context) {
+        let (codes, storages) = EVM_CHECKPOINTS.lock().revert_checkpoint();
+
+        for (address, old_code) in codes {
+            match old_code {
+                Some(code) => {
+                    let _ = AccountCodes::insert(ctx.state.write().borrow_mut(), &address, &code);
+                }
+                None => AccountCodes::remove(ctx.state.write().borrow_mut(), &address),
+            }
+            // The restored code may differ from whatever a read during the now-reverted
+            // sub-call cached -- without this, CODE_CACHE/BASIC_CACHE would keep serving
+            // the reverted-away value to every later read in the block.
+            invalidate_address(&address);
+        }
+
+        for ((address, index), old_value) in storages {
+            match old_value {
+                Some(value) => {
+                    let _ = AccountStorages::insert(
+                        ctx.state.write().borrow_mut(),
+                        &address,
+                        &index,
+                        &value,
+                    );
+                }
+                None => {
+                    AccountStorages::remove(ctx.state.write().borrow_mut(), &address, &index)
+                }
+            }
+            invalidate_address(&address);
+        }
     }
 
-    /// Get the account code
+    /// Get the account code. Cached by `(address, height)`; only the current committed
+    /// height (`height == None`) is ever kept fresh by writes, so a versioned lookup at
+    /// a non-current height is cached too (historical data never changes) but a current
+    /// lookup always reflects the latest `invalidate_address` call.
     pub fn account_codes(
         ctx: &Context,
         address: &H160,
         height: Option<u64>,
     ) -> Option<Vec<u8>> {
-        match height {
+        let key = (*address, height);
+        if let Some(hit) = CODE_CACHE.lock().get(&key) {
+            return hit.clone();
+        }
+
+        let value = match height {
             Some(ver) => AccountCodes::get_ver(ctx.state.read().borrow(), address, ver),
             None => AccountCodes::get(ctx.state.read().borrow(), address),
-        }
+        };
+        CODE_CACHE.lock().put(key, value.clone());
+        value
     }
 
-    /// Get the account storage
+    /// Get the account storage. See [`Self::account_codes`] for the caching policy.
     pub fn account_storages(
         ctx: &Context,
         address: &H160,
         index: &H256,
         height: Option<u64>,
     ) -> Option<H256> {
-        match height {
+        let key = (*address, *index, height);
+        if let Some(hit) = STORAGE_CACHE.lock().get(&key) {
+            return *hit;
+        }
+
+        let value = match height {
             Some(ver) => {
                 AccountStorages::get_ver(ctx.state.read().borrow(), address, index, ver)
             }
             None => AccountStorages::get(ctx.state.read().borrow(), address, index),
-        }
+        };
+        STORAGE_CACHE.lock().put(key, value);
+        value
     }
 
-    /// Get the account basic in EVM format.
+    /// Get the account basic in EVM format. Cached by address; invalidated alongside code
+    /// and storage whenever `invalidate_address` runs.
     pub fn account_basic(ctx: &Context, address: &H160) -> Account {
+        if let Some(hit) = BASIC_CACHE.lock().get(address) {
+            return hit.clone();
+        }
+
         let account_id = C::AddressMapping::convert_to_account_id(*address);
         let nonce = C::AccountAsset::nonce(ctx, &account_id);
         let balance = C::AccountAsset::balance(ctx, &account_id);
 
-        Account { balance, nonce }
+        let account = Account { balance, nonce };
+        BASIC_CACHE.lock().put(*address, account.clone());
+        account
     }
 
     /// Get the block proposer.
     pub fn find_proposer(ctx: &Context) -> H160 {
-        // TODO
         proposer_converter(ctx.header.proposer_address.clone()).unwrap_or_default()
     }
+
+    /// Records the base fee per gas for the block about to be delivered. Must be called
+    /// from `begin_block` before any transaction in the block settles its fee, so
+    /// `correct_and_deposit_fee` can split the fee into a burned base-fee portion and a
+    /// tip paid to the proposer.
+    pub fn set_base_fee_per_gas(base_fee_per_gas: U256) {
+        *BASE_FEE_PER_GAS.lock() = base_fee_per_gas;
+    }
 }
 
 /// Implements the transaction payment for a module implementing the `Currency`
@@ -84,19 +257,49 @@ impl<C: Config> App<C> {
 /// `OnUnbalanced`).
 impl<C: Config> OnChargeEVMTransaction for App<C> {
     fn withdraw_fee(ctx: &Context, who: &H160, fee: U256) -> Result<()> {
-        // TODO fee pay to block author
         let account_id = C::AddressMapping::convert_to_account_id(*who);
-        C::AccountAsset::withdraw(ctx, &account_id, fee)
+        let res = C::AccountAsset::withdraw(ctx, &account_id, fee);
+        invalidate_address(who);
+        res
     }
 
+    /// Refunds the unused portion of the withdrawn fee back to the payer, then settles
+    /// the rest: below `CFG.checkpoint.evm_fee_distribution_height` the whole corrected
+    /// fee is burned exactly as before, so existing chains keep their historical behavior;
+    /// at or above it, the fee is split EIP-1559-style into a `base_fee_per_gas * gas_used`
+    /// portion that is burned and a priority-tip remainder credited to the block proposer.
     fn correct_and_deposit_fee(
         ctx: &Context,
         who: &H160,
         corrected_fee: U256,
         already_withdrawn: U256,
+        gas_used: U256,
     ) -> Result<()> {
         let account_id = C::AddressMapping::convert_to_account_id(*who);
         C::AccountAsset::refund(ctx, &account_id, already_withdrawn)?;
-        C::AccountAsset::burn(ctx, &account_id, corrected_fee)
+        invalidate_address(who);
+
+        let height = ctx.header.height as u64;
+        if height < CFG.checkpoint.evm_fee_distribution_height {
+            let res = C::AccountAsset::burn(ctx, &account_id, corrected_fee);
+            invalidate_address(who);
+            return res;
+        }
+
+        let base_fee_per_gas = *BASE_FEE_PER_GAS.lock();
+        let base_fee_amount = base_fee_per_gas.saturating_mul(gas_used).min(corrected_fee);
+        let tip = corrected_fee.saturating_sub(base_fee_amount);
+
+        C::AccountAsset::burn(ctx, &account_id, base_fee_amount)?;
+        invalidate_address(who);
+
+        if !tip.is_zero() {
+            let proposer = App::<C>::find_proposer(ctx);
+            let proposer_account_id = C::AddressMapping::convert_to_account_id(proposer);
+            C::AccountAsset::refund(ctx, &proposer_account_id, tip)?;
+            invalidate_address(&proposer);
+        }
+
+        Ok(())
     }
 }
\ No newline at end of file